@@ -0,0 +1,528 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    parenthesized, parse::Parse, parse_macro_input, Data, DeriveInput, Error, Fields, Ident,
+    LitStr, Result, Token, Type,
+};
+
+const CODEC: &str = "crate::class::codec";
+const TRAITS: &str = "crate::class::traits";
+
+fn path(base: &str, item: &str) -> TokenStream2 {
+    format!("{}::{}", base, item).parse().unwrap()
+}
+
+#[derive(Default)]
+struct ClassOptions {
+    class: Option<LitStr>,
+    file: Option<LitStr>,
+    prefix: Option<LitStr>,
+    suffix: Option<LitStr>,
+    key: Option<Type>,
+    key_field: Option<LitStr>,
+    zeroed: Vec<LitStr>,
+}
+
+enum FieldKind {
+    Single,
+    Map,
+    Indexed,
+    Unknown(Type),
+    FileExists,
+    Skip,
+}
+
+struct FieldOptions {
+    ident: Ident,
+    ty: Type,
+    kind: Option<FieldKind>,
+    name: Option<LitStr>,
+    len_field: Option<LitStr>,
+    int_bool: bool,
+    text: Option<Type>,
+    with: Option<Type>,
+}
+
+impl ClassOptions {
+    fn parse(input: &DeriveInput) -> Result<Self> {
+        let mut options = Self::default();
+
+        for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("bepis")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("class") {
+                    options.class = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("file") {
+                    options.file = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("prefix") {
+                    options.prefix = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("suffix") {
+                    options.suffix = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("key") {
+                    options.key = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("key_field") {
+                    options.key_field = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("zeroed") {
+                    let content;
+                    parenthesized!(content in meta.input);
+                    options
+                        .zeroed
+                        .extend(content.parse_terminated(<LitStr as Parse>::parse, Token![,])?);
+                } else {
+                    return Err(meta.error("unknown bepis class attribute"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        if options.class.is_none() {
+            return Err(Error::new(
+                Span::call_site(),
+                "missing #[bepis(class = \"...\")]",
+            ));
+        }
+
+        match (&options.key, &options.file) {
+            (Some(_), None) if options.prefix.is_some() && options.suffix.is_some() => {}
+            (None, Some(_)) if options.prefix.is_none() && options.suffix.is_none() => {}
+            _ => {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "expected either `file` or `key`, `prefix` and `suffix`",
+                ))
+            }
+        }
+
+        if options.key_field.is_some() && options.key.is_none() {
+            return Err(Error::new(
+                Span::call_site(),
+                "`key_field` requires a `key`",
+            ));
+        }
+
+        Ok(options)
+    }
+}
+
+impl FieldOptions {
+    fn parse(field: &syn::Field) -> Result<Self> {
+        let mut options = Self {
+            ident: field.ident.clone().unwrap(),
+            ty: field.ty.clone(),
+            kind: None,
+            name: None,
+            len_field: None,
+            int_bool: false,
+            text: None,
+            with: None,
+        };
+
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("bepis")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("field") {
+                    options.name = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("len_field") {
+                    options.len_field = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("int_bool") {
+                    options.int_bool = true;
+                } else if meta.path.is_ident("text") {
+                    options.text = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("with") {
+                    options.with = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("map") {
+                    options.kind = Some(FieldKind::Map);
+                } else if meta.path.is_ident("indexed") {
+                    options.kind = Some(FieldKind::Indexed);
+                } else if meta.path.is_ident("unknown") {
+                    options.kind = Some(FieldKind::Unknown(meta.value()?.parse()?));
+                } else if meta.path.is_ident("file_exists") {
+                    options.kind = Some(FieldKind::FileExists);
+                } else if meta.path.is_ident("skip") {
+                    options.kind = Some(FieldKind::Skip);
+                } else {
+                    return Err(meta.error("unknown bepis field attribute"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        let codecs = options.int_bool as u8
+            + options.text.is_some() as u8
+            + options.with.is_some() as u8;
+
+        if codecs > 1 {
+            return Err(Error::new_spanned(
+                &field.ty,
+                "only one of `int_bool`, `text` and `with` may be used",
+            ));
+        }
+
+        match options.kind {
+            None if options.name.is_some() => options.kind = Some(FieldKind::Single),
+            Some(FieldKind::Indexed) if options.name.is_none() => {
+                return Err(Error::new_spanned(
+                    &field.ty,
+                    "`indexed` requires a `field`",
+                ))
+            }
+            Some(FieldKind::Indexed) if codecs > 0 => {
+                return Err(Error::new_spanned(
+                    &field.ty,
+                    "`indexed` fields use the value type's array encoding",
+                ))
+            }
+            Some(FieldKind::Unknown(_)) if options.name.is_none() => {
+                return Err(Error::new_spanned(
+                    &field.ty,
+                    "`unknown` requires a `field`",
+                ))
+            }
+            Some(FieldKind::Unknown(_)) if codecs > 0 || options.len_field.is_some() => {
+                return Err(Error::new_spanned(
+                    &field.ty,
+                    "`unknown` fields use the value type's array encoding",
+                ))
+            }
+            Some(FieldKind::Map) if options.name.is_some() => {
+                return Err(Error::new_spanned(
+                    &field.ty,
+                    "`map` fields take their names from the key's `FieldName`",
+                ))
+            }
+            None => {
+                return Err(Error::new_spanned(
+                    &field.ty,
+                    "expected `field`, `map`, `file_exists` or `skip`",
+                ))
+            }
+            _ => {}
+        }
+
+        Ok(options)
+    }
+
+    fn codec(&self) -> TokenStream2 {
+        if let Some(with) = &self.with {
+            quote!(#with)
+        } else if let Some(text) = &self.text {
+            let text_codec = path(CODEC, "Text");
+            quote!(#text_codec<#text>)
+        } else if self.int_bool {
+            path(CODEC, "IntBool")
+        } else {
+            path(CODEC, "Plain")
+        }
+    }
+
+    fn read(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        let ty = &self.ty;
+        let codec = self.codec();
+        let codec_trait = path(CODEC, "Codec");
+
+        let value = match self.kind.as_ref().unwrap() {
+            FieldKind::Single => {
+                let name = self.name.as_ref().unwrap();
+                quote!(<#codec as #codec_trait<#ty>>::read(class.fields.get(#name))?)
+            }
+            FieldKind::Map => {
+                let read_field_map = path(CODEC, "read_field_map");
+                quote!(#read_field_map::<#codec, _, _>(class)?)
+            }
+            FieldKind::Indexed => {
+                let name = self.name.as_ref().unwrap();
+                let read_indexed_map = path(CODEC, "read_indexed_map");
+                quote!(#read_indexed_map(class.fields.get(#name))?)
+            }
+            FieldKind::Unknown(key) => {
+                let name = self.name.as_ref().unwrap();
+                let read_unknown_indexes = path(CODEC, "read_unknown_indexes");
+                quote!(#read_unknown_indexes::<#key, _>(class.fields.get(#name))?)
+            }
+            FieldKind::FileExists => quote!(true),
+            FieldKind::Skip => quote!(::std::default::Default::default()),
+        };
+
+        quote!(#ident: #value,)
+    }
+
+    fn write(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        let ty = &self.ty;
+        let codec = self.codec();
+        let codec_trait = path(CODEC, "Codec");
+
+        let length = self.len_field.as_ref().map(|len_field| {
+            quote! {
+                fields.insert(
+                    #len_field.to_string(),
+                    ::ms_nrbf::Field::Primitive(::ms_nrbf::Primitive::Int32(
+                        self.#ident.len() as i32,
+                    )),
+                );
+            }
+        });
+
+        let value = match self.kind.as_ref().unwrap() {
+            FieldKind::Single => {
+                let name = self.name.as_ref().unwrap();
+                quote! {
+                    fields.insert(
+                        #name.to_string(),
+                        <#codec as #codec_trait<#ty>>::write(&self.#ident)?,
+                    );
+                }
+            }
+            FieldKind::Map => {
+                let write_field_map = path(CODEC, "write_field_map");
+                quote!(#write_field_map::<#codec, _, _>(&mut fields, &self.#ident)?;)
+            }
+            FieldKind::Indexed => {
+                let name = self.name.as_ref().unwrap();
+                let write_indexed_map = path(CODEC, "write_indexed_map");
+                quote! {
+                    fields.insert(#name.to_string(), #write_indexed_map(&self.#ident));
+                }
+            }
+            FieldKind::Unknown(_) => {
+                let name = self.name.as_ref().unwrap();
+                let write_unknown_indexes = path(CODEC, "write_unknown_indexes");
+                quote!(#write_unknown_indexes(&mut fields, #name, &self.#ident)?;)
+            }
+            FieldKind::FileExists | FieldKind::Skip => quote!(),
+        };
+
+        quote!(#length #value)
+    }
+}
+
+/// Derives `ParsableClass` (or `ParsableClassKeyed` when a `key` is given)
+/// from `#[bepis(...)]` attributes on the struct and its fields.
+///
+/// Struct attributes:
+/// - `class = "..."`: the .NET class name written to the stream.
+/// - `file = "..."`: the file name of an unkeyed class.
+/// - `key = Type`, `prefix = "..."`, `suffix = "..."`: the key enum and file
+///   name parts of a keyed class. Keyed classes must provide
+///   `fn new(key: &Type) -> Self` for slots without a file.
+/// - `key_field = "..."`: an `Int32` field holding the key, written on save.
+/// - `zeroed("...", ...)`: `Int32` fields always written as 0.
+///
+/// Field attributes:
+/// - `field = "..."`: read and write the named field.
+/// - `int_bool`: a `bool` stored as an `Int32`.
+/// - `text = Type`: a `String` (or `Vec<String>`) holding a number of `Type`.
+/// - `with = Type`: a custom `Codec` for the field.
+/// - `len_field = "..."`: an `Int32` field holding the field's length.
+/// - `map`: a `BTreeMap` whose keys each name their own field through
+///   `FieldName`.
+/// - `indexed`: a `BTreeMap` stored as one array indexed by `ArrayIndex`.
+///   Every `indexed` field needs an `unknown` field for the same array.
+/// - `unknown = Type`: a `BTreeMap<usize, _>` of the indexes in an `indexed`
+///   field's array that `Type` has no variant for, so they survive a save.
+///   Must come after that field.
+/// - `file_exists`: set to `true` when parsed.
+/// - `skip`: not stored, set to its default when parsed.
+#[proc_macro_derive(BepisClass, attributes(bepis))]
+pub fn derive_bepis_class(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand_bepis_class(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand_bepis_class(input: &DeriveInput) -> Result<TokenStream2> {
+    let options = ClassOptions::parse(input)?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields
+                .named
+                .iter()
+                .map(FieldOptions::parse)
+                .collect::<Result<Vec<_>>>()?,
+            _ => {
+                return Err(Error::new_spanned(
+                    input,
+                    "BepisClass requires named fields",
+                ))
+            }
+        },
+        _ => return Err(Error::new_spanned(input, "BepisClass requires a struct")),
+    };
+
+    for field in &fields {
+        let Some(FieldKind::Indexed) = field.kind else {
+            continue;
+        };
+        let name = field.name.as_ref().unwrap().value();
+
+        if !fields.iter().any(|other| {
+            matches!(other.kind, Some(FieldKind::Unknown(_)))
+                && other.name.as_ref().is_some_and(|other| other.value() == name)
+        }) {
+            return Err(Error::new_spanned(
+                &field.ty,
+                "`indexed` fields need an `unknown` field for the same array",
+            ));
+        }
+    }
+
+    let file_exists = fields
+        .iter()
+        .find(|field| matches!(field.kind, Some(FieldKind::FileExists)))
+        .map(|field| &field.ident)
+        .ok_or_else(|| Error::new_spanned(input, "missing #[bepis(file_exists)] field"))?;
+
+    let name = &input.ident;
+    let class_name = options.class.as_ref().unwrap();
+    let reads = fields.iter().map(FieldOptions::read);
+    let writes = fields.iter().map(FieldOptions::write);
+    let zeroed = options.zeroed.iter().map(|field| {
+        quote! {
+            fields.insert(
+                #field.to_string(),
+                ::ms_nrbf::Field::Primitive(::ms_nrbf::Primitive::Int32(0)),
+            );
+        }
+    });
+    let key_field = options.key_field.as_ref().map(|key_field| {
+        quote! {
+            fields.insert(
+                #key_field.to_string(),
+                ::ms_nrbf::Field::Primitive(::ms_nrbf::Primitive::Int32(*variant as i32)),
+            );
+        }
+    });
+    let field_map = path(TRAITS, "FieldMap");
+
+    let parse = quote! {
+        fn parse(class: &::ms_nrbf::Class) -> Option<Self> {
+            Some(Self {
+                #(#reads)*
+            })
+        }
+    };
+    let get_file_exists = quote! {
+        fn get_file_exists(&self) -> bool {
+            self.#file_exists
+        }
+    };
+    let unparse_body = quote! {
+        let mut fields = ::indexmap::IndexMap::new();
+
+        #(#writes)*
+        #key_field
+        #(#zeroed)*
+
+        Some(fields)
+    };
+
+    Ok(match &options.key {
+        Some(key) => {
+            let parsable = path(TRAITS, "ParsableClassKeyed");
+            let prefix = options.prefix.as_ref().unwrap();
+            let suffix = options.suffix.as_ref().unwrap();
+
+            quote! {
+                impl #parsable<#key> for #name {
+                    const CLASS_NAME: &'static str = #class_name;
+                    const FILE_PREFIX: &'static str = #prefix;
+                    const FILE_SUFFIX: &'static str = #suffix;
+
+                    #get_file_exists
+
+                    fn create_new(variant: &#key) -> Self {
+                        Self::new(variant)
+                    }
+
+                    #parse
+
+                    #[allow(unused_variables)]
+                    fn unparse(&self, variant: &#key) -> Option<#field_map> {
+                        #unparse_body
+                    }
+                }
+            }
+        }
+        None => {
+            let parsable = path(TRAITS, "ParsableClass");
+            let file = options.file.as_ref().unwrap();
+
+            quote! {
+                impl #parsable for #name {
+                    const CLASS_NAME: &'static str = #class_name;
+                    const FILE_NAME: &'static str = #file;
+
+                    #get_file_exists
+
+                    #parse
+
+                    fn unparse(&self) -> Option<#field_map> {
+                        #unparse_body
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Derives `FieldName` for a fieldless enum whose variants each carry
+/// `#[bepis(field = "...")]`.
+#[proc_macro_derive(FieldName, attributes(bepis))]
+pub fn derive_field_name(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand_field_name(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand_field_name(input: &DeriveInput) -> Result<TokenStream2> {
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => return Err(Error::new_spanned(input, "FieldName requires an enum")),
+    };
+
+    let mut arms = vec![];
+
+    for variant in variants {
+        let mut name: Option<LitStr> = None;
+
+        for attr in variant.attrs.iter().filter(|attr| attr.path().is_ident("bepis")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("field") {
+                    name = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown bepis variant attribute"))
+                }
+            })?;
+        }
+
+        let ident = &variant.ident;
+        let name = name.ok_or_else(|| {
+            Error::new_spanned(variant, "missing #[bepis(field = \"...\")]")
+        })?;
+
+        arms.push(quote!(Self::#ident => #name,));
+    }
+
+    let name = &input.ident;
+    let field_name = path(TRAITS, "FieldName");
+
+    Ok(quote! {
+        impl #field_name for #name {
+            fn field_name(&self) -> &'static str {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
@@ -1,37 +1,117 @@
 use crate::{
-    class::classes::Classes,
-    enums::{Act, Difficulty, Level, LevelRank, Lockable, SaveSlot, SecretLevel, WeaponType},
+    archive::{export_slot, SlotArchive},
+    bulk::{BulkAction, BulkEdit, Scope},
+    loadout::{get_conflicts, unlock_weapon},
+    pattern::{
+        get_pattern_path, get_patterns_dir, list_patterns, Mirror, Pattern, Prefab, PATTERN_SIZE,
+    },
+    preset::{get_builtin_presets, load_presets, Preset},
+    primes::{set_level_rank, Prime},
+    progression::Progression,
+    class::{
+        classes::Classes,
+        cybergrind::{format_time, parse_time, split_wave},
+        general::GeneralData,
+        repair::FileRepair,
+        storage::{DirectoryStorage, MemoryStorage},
+    },
+    enums::{
+        Act, CampaignPosition, Difficulty, EnemyType, Layer, Level, LevelRank, Lockable,
+        SecretLevel, WeaponType,
+    },
+    shop::{buy, get_buyable, get_spent, refund},
+    saves::{
+        add_bookmark, detect_saves_root, detect_slots, get_slot_name, get_slot_summaries,
+        remove_bookmark, Slot, SlotOperation, SlotSummary,
+    },
+    report::{Report, ReportFormat},
+    sandbox::{
+        delete_save, duplicate_save, get_sandbox_dir, get_save_name, list_sandbox_saves,
+        rename_save, Quaternion, SandboxCategory, SandboxObject, SandboxSave, Vector,
+    },
+    stats::{Stats, COMPLETION_COLUMNS},
+    validate::{validate, Warning},
 };
 use eframe::{
-    egui::{Button, CentralPanel, ComboBox, Context, Layout, ScrollArea, TextEdit, Ui},
-    emath::Align,
+    egui::{
+        vec2, Button, CentralPanel, Checkbox, CollapsingHeader, Color32, ComboBox, Context,
+        DragValue, Grid, Layout, RichText, ScrollArea, TextEdit, Ui, Window,
+    },
+    emath::{Align, Align2},
     App, CreationContext, Frame,
 };
-use registry::{Data, Hive, Security};
-use std::path::{Path, PathBuf};
+use std::{
+    fs::{write, File},
+    hash::Hash,
+    io,
+    path::{Path, PathBuf},
+};
 use strum::IntoEnumIterator;
 
 pub struct SaveEditorApp {
     save_path: Option<PathBuf>,
     classes: Option<Classes>,
 
-    save_slot: SaveSlot,
+    slots: Vec<Slot>,
+    save_slot: usize,
     path_edit: String,
     load_enabled: bool,
     save_enabled: bool,
     difficulty: Difficulty,
+
+    archive_path: String,
+    status: String,
+
+    first_slot: usize,
+    second_slot: usize,
+    pending_slot_operation: Option<(SlotOperation, Vec<(String, SlotSummary)>)>,
+    pending_import: Option<(SlotArchive, Vec<(String, SlotSummary)>)>,
+    pending_preset: Option<(usize, Vec<(String, SlotSummary)>)>,
+    slot_overview: Option<Vec<(Slot, SlotSummary)>>,
+    show_stats: bool,
+    show_rank_grid: bool,
+    show_primes: bool,
+    show_bestiary: bool,
+    show_unused_weapons: bool,
+    cybergrind_time_edit: Option<(usize, String)>,
+    show_patterns: bool,
+    patterns_dir: String,
+    pattern_files: Vec<PathBuf>,
+    pattern_name: String,
+    pattern: Option<Pattern>,
+    pattern_paint_prefab: bool,
+    pattern_painting: bool,
+    pending_pattern_overwrite: Option<PathBuf>,
+    pattern_height: i32,
+    pattern_prefab: Prefab,
+    show_sandbox: bool,
+    sandbox_dir: String,
+    sandbox_files: Vec<PathBuf>,
+    sandbox_path: Option<PathBuf>,
+    sandbox_name: String,
+    sandbox: Option<SandboxSave>,
+    bulk_all_difficulties: bool,
+    report_path: String,
+    report_format: ReportFormat,
+    presets: Vec<Preset>,
+    preset: usize,
+    progression_level: Level,
+    progression_rank: LevelRank,
+    save_warnings: Option<Vec<Warning>>,
+    repairs: Option<Vec<FileRepair>>,
 }
 
 impl<'a> SaveEditorApp {
     pub fn new(_cc: &CreationContext) -> Self {
-        let save_path = detect_save_path(&SaveSlot::One);
+        let slots = detect_slots();
+        let save_path = slots.first().map(|slot| slot.path.clone());
         let load_enabled = save_path.is_some();
         let classes;
         let save_enabled;
         let path_edit;
         match &save_path {
             Some(save_path) => {
-                classes = Classes::load(save_path);
+                classes = Classes::load(&DirectoryStorage::new(save_path));
                 path_edit = save_path.to_string_lossy().to_string();
                 save_enabled = true;
             }
@@ -42,15 +122,148 @@ impl<'a> SaveEditorApp {
             }
         };
 
+        let (presets, status) = match load_presets() {
+            Ok(presets) => (presets, String::new()),
+            Err(error) => (
+                get_builtin_presets(),
+                format!("Loading presets failed: {}", error),
+            ),
+        };
+
+        let saves_root = detect_saves_root();
+        let patterns_dir = saves_root
+            .as_deref()
+            .and_then(get_patterns_dir)
+            .map(|dir| dir.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let sandbox_dir = saves_root
+            .as_deref()
+            .map(|saves_root| get_sandbox_dir(saves_root).to_string_lossy().to_string())
+            .unwrap_or_default();
+
         Self {
             save_path,
             classes,
 
-            save_slot: SaveSlot::One,
+            slots,
+            save_slot: 0,
             path_edit,
             load_enabled,
             save_enabled,
             difficulty: Difficulty::Standard,
+
+            archive_path: String::new(),
+            status,
+
+            first_slot: 0,
+            second_slot: 1,
+            pending_slot_operation: None,
+            pending_import: None,
+            pending_preset: None,
+            slot_overview: None,
+            show_stats: false,
+            show_rank_grid: false,
+            show_primes: false,
+            show_bestiary: false,
+            show_unused_weapons: false,
+            cybergrind_time_edit: None,
+            show_patterns: false,
+            patterns_dir,
+            pattern_files: vec![],
+            pattern_name: String::new(),
+            pattern: None,
+            pattern_paint_prefab: false,
+            pattern_painting: false,
+            pending_pattern_overwrite: None,
+            pattern_height: 0,
+            pattern_prefab: Prefab::MeleeSpawn,
+            show_sandbox: false,
+            sandbox_dir,
+            sandbox_files: vec![],
+            sandbox_path: None,
+            sandbox_name: String::new(),
+            sandbox: None,
+            bulk_all_difficulties: false,
+            report_path: String::new(),
+            report_format: ReportFormat::Markdown,
+            presets,
+            preset: 0,
+            progression_level: Level::IntoTheFire,
+            progression_rank: LevelRank::S,
+            save_warnings: None,
+            repairs: None,
+        }
+    }
+
+    fn select_slot(&mut self, index: usize) {
+        if let Some(slot) = self.slots.get(index) {
+            self.save_slot = index;
+            self.path_edit = slot.path.to_string_lossy().to_string();
+            self.save_path = Some(slot.path.clone());
+            self.load_enabled = true;
+        }
+    }
+
+    fn update_bookmarks(&mut self, result: io::Result<()>) {
+        if let Err(error) = result {
+            self.status = format!("Updating bookmarks failed: {}", error);
+            return;
+        }
+
+        let save_path = self.save_path.clone();
+        self.slots = detect_slots();
+
+        if let Some(index) = save_path
+            .and_then(|path| self.slots.iter().position(|slot| slot.path == path))
+        {
+            self.save_slot = index;
+        }
+    }
+
+    fn save(&mut self) {
+        if let Some(save_path) = &self.save_path {
+            if let Some(classes) = &self.classes {
+                if let Err(error) = classes.save(&mut DirectoryStorage::new(save_path)) {
+                    self.status = format!("Saving failed: {}", error);
+                }
+            }
+        }
+    }
+
+    fn repair(&mut self) {
+        let Some(save_path) = &self.save_path else {
+            return;
+        };
+
+        // Repairs are tried on a copy first, so they can be reviewed before
+        // anything on disk changes.
+        let preview = MemoryStorage::copy_from(&DirectoryStorage::new(save_path))
+            .and_then(|mut preview| Classes::repair(&mut preview));
+
+        match preview {
+            Ok(repairs) if repairs.is_empty() => {
+                self.status = "Nothing to repair".to_string();
+            }
+            Ok(repairs) => self.repairs = Some(repairs),
+            Err(error) => self.status = format!("Repair failed: {}", error),
+        }
+    }
+
+    fn apply_repairs(&mut self) {
+        let Some(save_path) = &self.save_path else {
+            return;
+        };
+
+        match Classes::repair(&mut DirectoryStorage::new(save_path)) {
+            Ok(repairs) => {
+                self.status = format!(
+                    "Repaired {} files",
+                    repairs.iter().filter(|repair| repair.is_repaired()).count()
+                );
+                self.classes = Classes::load(&DirectoryStorage::new(save_path));
+                self.save_enabled = self.classes.is_some();
+            }
+            Err(error) => self.status = format!("Repair failed: {}", error),
         }
     }
 
@@ -73,39 +286,53 @@ impl<'a> SaveEditorApp {
             }
 
             if ui.button("Auto-detect").clicked() {
-                if let Some(save_path) = detect_save_path(&self.save_slot) {
-                    self.path_edit = save_path.to_string_lossy().to_string();
-                    self.save_path = Some(save_path);
-                    self.load_enabled = true;
-                }
+                self.slots = detect_slots();
+                self.select_slot(self.save_slot.min(self.slots.len().saturating_sub(1)));
             }
 
             ui.add_space(5.0);
             ui.label("Save Slot:");
 
-            ComboBox::from_id_source("save slot")
-                .selected_text((self.save_slot as u8).to_string())
-                .width(20.0)
-                .show_ui(ui, |ui| {
-                    ui.selectable_value(&mut self.save_slot, SaveSlot::One, "1");
-                    ui.selectable_value(&mut self.save_slot, SaveSlot::Two, "2");
-                    ui.selectable_value(
-                        &mut self.save_slot,
-                        SaveSlot::Three,
-                        "3",
-                    );
-                    ui.selectable_value(&mut self.save_slot, SaveSlot::Four, "4");
-                    ui.selectable_value(&mut self.save_slot, SaveSlot::Five, "5");
-                });
+            let save_slot = self.save_slot;
+            slot_combo_box(ui, "save slot", &self.slots, &mut self.save_slot);
+            if self.save_slot != save_slot {
+                self.select_slot(self.save_slot);
+            }
+
+            match self.slots.get(self.save_slot) {
+                Some(slot) if slot.bookmarked => {
+                    if ui.button("Remove bookmark").clicked() {
+                        let path = slot.path.clone();
+                        self.update_bookmarks(remove_bookmark(&path));
+                    }
+                }
+                _ => {
+                    let bookmark = self
+                        .save_path
+                        .as_ref()
+                        .filter(|path| !self.slots.iter().any(|slot| slot.path == **path))
+                        .cloned();
+
+                    if ui
+                        .add_enabled(bookmark.is_some(), Button::new("Bookmark"))
+                        .clicked()
+                    {
+                        if let Some(path) = bookmark {
+                            self.update_bookmarks(add_bookmark(&path));
+                        }
+                    }
+                }
+            }
 
-            ui.add_space(140.0);
+            ui.add_space(20.0);
 
             if ui
                 .add_enabled(self.load_enabled, Button::new("Load"))
                 .clicked()
             {
                 if let Some(save_path) = &self.save_path {
-                    self.save_enabled = match Classes::load(save_path) {
+                    let storage = DirectoryStorage::new(save_path);
+                    self.save_enabled = match Classes::load(&storage) {
                         Some(classes) => {
                             self.classes = Some(classes);
                             true
@@ -119,13 +346,58 @@ impl<'a> SaveEditorApp {
                 .add_enabled(self.save_enabled, Button::new("Save"))
                 .clicked()
             {
-                if let Some(save_path) = &self.save_path {
-                    if let Some(classes) = &self.classes {
-                        classes.save(&save_path).ok();
+                if let Some(classes) = &self.classes {
+                    let warnings = validate(classes);
+                    match warnings.is_empty() {
+                        true => self.save(),
+                        false => self.save_warnings = Some(warnings),
                     }
                 }
             }
 
+            if ui
+                .add_enabled(self.load_enabled, Button::new("Repair"))
+                .on_hover_text("Rewrite malformed save files, keeping the originals as .bak files")
+                .clicked()
+            {
+                self.repair();
+            }
+
+            if ui
+                .add_enabled(self.classes.is_some(), Button::new("Statistics"))
+                .clicked()
+            {
+                self.show_stats = !self.show_stats;
+            }
+
+            if ui
+                .add_enabled(self.classes.is_some(), Button::new("Rank grid"))
+                .clicked()
+            {
+                self.show_rank_grid = !self.show_rank_grid;
+            }
+
+            if ui
+                .add_enabled(self.classes.is_some(), Button::new("Primes"))
+                .clicked()
+            {
+                self.show_primes = !self.show_primes;
+            }
+
+            if ui
+                .add_enabled(self.classes.is_some(), Button::new("Bestiary"))
+                .clicked()
+            {
+                self.show_bestiary = !self.show_bestiary;
+            }
+
+            if ui.button("Sandbox").clicked() {
+                self.show_sandbox = !self.show_sandbox;
+                if self.show_sandbox {
+                    self.refresh_sandbox();
+                }
+            }
+
             ui.with_layout(
                 Layout::right_to_left(Align::Center),
                 |ui| {
@@ -158,194 +430,1279 @@ impl<'a> SaveEditorApp {
         });
     }
 
-    fn update_single_level(&mut self, ui: &mut Ui, level: &Level) -> Option<()> {
-        let difficulty = self.difficulty as usize;
-
-        let classes = self.classes.as_mut()?;
-        let level_data = classes.levels.get_mut(level)?;
+    fn update_archive_bar(&mut self, ui: &mut Ui) {
         ui.horizontal(|ui| {
-            ui.label("Rank: ");
+            ui.add_space(5.0);
+            ui.label("Archive path: ");
+            ui.add(TextEdit::singleline(&mut self.archive_path).desired_width(437.0));
 
-            let rank = level_data.ranks.get_mut(difficulty)?;
+            let enabled = self.save_path.is_some() && !self.archive_path.is_empty();
 
-            ComboBox::from_id_source(format!("level {} rank", *level as u16))
-                .selected_text(rank.to_string())
-                .width(70.0)
-                .show_ui(ui, |ui| {
-                    ui.selectable_value(rank, LevelRank::None, "None");
-                    ui.selectable_value(rank, LevelRank::D, "D");
-                    ui.selectable_value(rank, LevelRank::C, "C");
-                    ui.selectable_value(rank, LevelRank::B, "B");
-                    ui.selectable_value(rank, LevelRank::A, "A");
-                    ui.selectable_value(rank, LevelRank::S, "S");
-                    ui.selectable_value(rank, LevelRank::P, "P");
+            if ui
+                .add_enabled(enabled, Button::new("Export slot"))
+                .clicked()
+            {
+                self.status = match self.export_archive() {
+                    Ok(status) => status,
+                    Err(error) => format!("Export failed: {}", error),
+                };
+            }
 
-                    Some(())
-                });
+            if ui
+                .add_enabled(enabled, Button::new("Import slot"))
+                .clicked()
+            {
+                if let Err(error) = self.prepare_import() {
+                    self.status = format!("Import failed: {}", error);
+                }
+            }
 
-            Some(())
+            ui.add_space(5.0);
+            ui.label(&self.status);
         });
+    }
 
-        if level.is_prime() {
-            ui.horizontal(|ui| {
-                ui.label("State: ");
+    fn update_preset_bar(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.add_space(5.0);
+            ui.label("Preset: ");
+
+            ComboBox::from_id_source("preset")
+                .selected_text(
+                    self.presets
+                        .get(self.preset)
+                        .map(|preset| preset.name.clone())
+                        .unwrap_or_default(),
+                )
+                .width(100.0)
+                .show_ui(ui, |ui| {
+                    for (i, preset) in self.presets.iter().enumerate() {
+                        ui.selectable_value(&mut self.preset, i, &preset.name)
+                            .on_hover_text(&preset.description);
+                    }
+                });
 
-                let difficulty_data = classes.difficulty.get_mut(&self.difficulty)?;
-                let state = difficulty_data
-                    .prime_levels
-                    .get_mut(level.get_prime_index()? as usize)?;
+            if ui
+                .add_enabled(self.preset < self.presets.len(), Button::new("Apply preset"))
+                .clicked()
+            {
+                if let Err(error) = self.prepare_preset() {
+                    self.status = format!("Applying preset failed: {}", error);
+                }
+            }
 
-                ComboBox::from_id_source(format!("level {} state", *level as u16))
-                    .selected_text(state.to_string())
-                    .show_ui(ui, |ui| {
-                        if ui
-                            .selectable_value(state, Lockable::Locked, "Locked")
-                            .clicked()
-                        {
-                            difficulty_data.file_exists = true;
-                        }
-                        if ui
-                            .selectable_value(state, Lockable::Unlocked, "Unlocked")
-                            .clicked()
-                        {
-                            difficulty_data.file_exists = true;
-                        }
-                        if ui
-                            .selectable_value(state, Lockable::Completed, "Completed")
-                            .clicked()
-                        {
-                            difficulty_data.file_exists = true;
-                        }
-                    });
+            ui.add_space(20.0);
+            ui.label("Play through: ");
 
-                Some(())
-            });
-        }
+            ComboBox::from_id_source("progression level")
+                .selected_text(self.progression_level.to_string())
+                .width(220.0)
+                .show_ui(ui, |ui| {
+                    for level in Level::iter() {
+                        ui.selectable_value(&mut self.progression_level, level, level.to_string());
+                    }
+                });
 
-        if level_data.secrets_found.len() > 0 {
-            ui.horizontal(|ui| {
-                ui.label("Secrets found: ");
-                for secret in &mut level_data.secrets_found {
-                    ui.checkbox(secret, "");
-                }
-            });
-        }
+            ui.label("with");
+            rank_combo_box(ui, "progression rank", &mut self.progression_rank, 30.0);
 
-        ui.horizontal(|ui| {
-            ui.label("Challenge completed:");
-            ui.checkbox(&mut level_data.challenge, "");
+            if ui.button("Generate").clicked() {
+                let progression = Progression {
+                    level: self.progression_level,
+                    difficulty: self.difficulty,
+                    rank: self.progression_rank,
+                };
+
+                progression.apply(self.classes.get_or_insert_with(Classes::default));
+                self.save_enabled = self.save_path.is_some();
+                self.status = format!("{}, save to write it to the slot", progression);
+            }
         });
+    }
 
+    fn update_slot_bar(&mut self, ui: &mut Ui) {
         ui.horizontal(|ui| {
-            ui.label("Used major assists:");
-            ui.checkbox(
-                level_data.major_assists.get_mut(difficulty)?,
-                "",
-            );
+            ui.add_space(5.0);
+            ui.label("Slots: ");
 
-            Some(())
-        });
+            slot_combo_box(ui, "first slot", &self.slots, &mut self.first_slot);
+            slot_combo_box(ui, "second slot", &self.slots, &mut self.second_slot);
+
+            let (Some(first), Some(second)) = (
+                self.slots.get(self.first_slot).map(|slot| slot.path.clone()),
+                self.slots.get(self.second_slot).map(|slot| slot.path.clone()),
+            ) else {
+                ui.label("No save slots detected");
+                return;
+            };
+
+            let distinct = first != second;
+
+            let mut operation = None;
 
-        ui.horizontal(|ui| {
             if ui
-                .add_enabled(
-                    !level_data.file_exists,
-                    Button::new("Create file"),
-                )
+                .add_enabled(distinct, Button::new("Copy first to second"))
                 .clicked()
             {
-                level_data.file_exists = true
+                operation = Some(SlotOperation::Copy {
+                    source: first.clone(),
+                    target: second.clone(),
+                });
             }
 
             if ui
-                .add_enabled(
-                    level_data.file_exists,
-                    Button::new("Delete file"),
-                )
+                .add_enabled(distinct, Button::new("Swap"))
                 .clicked()
             {
-                level_data.file_exists = false
+                operation = Some(SlotOperation::Swap(first.clone(), second));
             }
-        });
 
-        Some(())
-    }
+            if ui.button("Clear first").clicked() {
+                operation = Some(SlotOperation::Clear(first));
+            }
 
-    fn update_secret_level(&mut self, ui: &mut Ui, secret_level: &SecretLevel) {
-        if !secret_level.is_prime() {
-            ui.collapsing(secret_level.to_string(), |ui| {
-                ui.horizontal(|ui| {
-                    ui.label("State: ");
+            ui.add_space(5.0);
 
-                    let state = self
-                        .classes
-                        .as_mut()?
-                        .general
-                        .secret_missions
-                        .get_mut(&secret_level)?;
+            if ui.button("Overview").clicked() {
+                match get_slot_summaries(&self.slots) {
+                    Ok(summaries) => self.slot_overview = Some(summaries),
+                    Err(error) => self.status = format!("Overview failed: {}", error),
+                }
+            }
 
-                    ComboBox::from_id_source(format!(
-                        "secret_level {} state",
-                        *secret_level as u8
-                    ))
-                    .selected_text(state.to_string())
-                    .show_ui(ui, |ui| {
-                        ui.selectable_value(state, Lockable::Locked, "Locked");
-                        ui.selectable_value(state, Lockable::Unlocked, "Unlocked");
-                        ui.selectable_value(state, Lockable::Completed, "Completed");
+            if let Some(operation) = operation {
+                match operation.get_summaries() {
+                    Ok(summaries) => self.pending_slot_operation = Some((operation, summaries)),
+                    Err(error) => self.status = format!("{} failed: {}", operation, error),
+                }
+            }
+        });
+    }
+
+    fn update_save_warnings(&mut self, ctx: &Context) {
+        let Some(warnings) = &self.save_warnings else {
+            return;
+        };
+
+        let mut confirmed = false;
+        let mut cancelled = false;
+
+        Window::new("Save warnings")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("The save has contradictions the game may not handle:");
+                ui.add_space(5.0);
+
+                ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    Grid::new("save warnings").striped(true).show(ui, |ui| {
+                        for warning in warnings {
+                            ui.monospace(&warning.path);
+                            ui.vertical(|ui| {
+                                ui.label(&warning.message);
+                                ui.label(RichText::new(&warning.fix).weak());
+                            });
+                            ui.end_row();
+                        }
                     });
+                });
 
-                    Some(())
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    confirmed = ui.button("Save anyway").clicked();
+                    cancelled = ui.button("Cancel").clicked();
                 });
             });
+
+        if confirmed {
+            self.save_warnings = None;
+            self.save();
+        } else if cancelled {
+            self.save_warnings = None;
         }
     }
 
-    fn update_levels(&mut self, ui: &mut Ui) {
-        ui.group(|ui| {
-            ui.heading("Levels");
-            ui.add_space(10.0);
+    fn update_repairs(&mut self, ctx: &Context) {
+        let Some(repairs) = &self.repairs else {
+            return;
+        };
 
-            ScrollArea::vertical().show(ui, |ui| {
-                ui.set_height(ui.available_height());
-                ui.set_width(350.0);
-                for act in Act::iter() {
-                    ui.collapsing(act.to_string(), |ui| {
-                        for layer in act.get_layers() {
-                            ui.collapsing(layer.to_string(), |ui| {
-                                for level in layer.get_levels() {
-                                    ui.collapsing(level.to_string(), |ui| {
-                                        self.update_single_level(ui, level);
-                                    });
-                                }
-                                self.update_secret_level(ui, &layer.get_secret_level());
-                            });
+        let repairable = repairs.iter().any(|repair| repair.is_repaired());
+        let mut confirmed = false;
+        let mut cancelled = false;
+
+        Window::new("Repair preview")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("These files will be rewritten, keeping the originals next to them.");
+                ui.label("The slot is reloaded afterwards, so unsaved changes will be lost.");
+                ui.add_space(5.0);
+
+                ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    for repair in repairs {
+                        ui.strong(repair.to_string());
+                        for change in &repair.changes {
+                            ui.label(format!("  {}", change));
                         }
-                    });
-                }
+                        ui.add_space(5.0);
+                    }
+                });
+
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    confirmed = ui.add_enabled(repairable, Button::new("Repair")).clicked();
+                    cancelled = ui.button("Cancel").clicked();
+                });
             });
-        });
+
+        if confirmed {
+            self.repairs = None;
+            self.apply_repairs();
+        } else if cancelled {
+            self.repairs = None;
+        }
     }
 
-    fn update_general(&mut self, ui: &mut Ui) {
-        ui.group(|ui| {
-            let available_width = ui.available_width();
-            ui.heading("General data");
-            ui.add_space(10.0);
+    fn update_slot_confirmation(&mut self, ctx: &Context) {
+        let Some((operation, summaries)) = &self.pending_slot_operation else {
+            return;
+        };
 
-            let classes = self.classes.as_mut()?;
+        let mut confirmed = false;
+        let mut cancelled = false;
 
-            ui.horizontal(|ui| {
-                ui.label("Money:");
-                if ui
-                    .text_edit_singleline(&mut classes.general.money)
-                    .changed()
-                {
-                    validate_u32(&mut classes.general.money);
+        Window::new("Confirm slot operation")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.heading(operation.to_string());
+                ui.add_space(5.0);
+
+                for (name, summary) in summaries {
+                    ui.label(format!("{}: {}", name, summary));
                 }
-            });
 
-            ui.horizontal(|ui| {
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    confirmed = ui.button("Confirm").clicked();
+                    cancelled = ui.button("Cancel").clicked();
+                });
+            });
+
+        if cancelled {
+            self.pending_slot_operation = None;
+        } else if confirmed {
+            let (operation, _) = self.pending_slot_operation.take().unwrap();
+
+            self.status = match operation.apply().and_then(|_| operation.get_summaries()) {
+                Ok(summaries) => format!(
+                    "{} done. {}",
+                    operation,
+                    summaries
+                        .iter()
+                        .map(|(name, summary)| format!("{}: {}", name, summary))
+                        .collect::<Vec<String>>()
+                        .join(". ")
+                ),
+                Err(error) => format!("{} failed: {}", operation, error),
+            };
+
+            if let Some(save_path) = &self.save_path {
+                if operation.get_slots().contains(&save_path.as_path()) {
+                    self.classes = Classes::load(&DirectoryStorage::new(save_path));
+                }
+            }
+        }
+    }
+
+    fn update_import_confirmation(&mut self, ctx: &Context) {
+        let Some((slot_archive, summaries)) = &self.pending_import else {
+            return;
+        };
+
+        let mut confirmed = false;
+        let mut cancelled = false;
+
+        Window::new("Confirm import")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.heading(format!(
+                    "Replace the slot with the archive of {}",
+                    slot_archive.manifest.source_slot
+                ));
+                ui.label("Unsaved changes to the slot will be lost.");
+                ui.add_space(5.0);
+
+                for (name, summary) in summaries {
+                    ui.label(format!("{}: {}", name, summary));
+                }
+
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    confirmed = ui.button("Confirm").clicked();
+                    cancelled = ui.button("Cancel").clicked();
+                });
+            });
+
+        if cancelled {
+            self.pending_import = None;
+        } else if confirmed {
+            let (slot_archive, _) = self.pending_import.take().unwrap();
+
+            self.status = match self.import_archive(&slot_archive) {
+                Ok(status) => status,
+                Err(error) => format!("Import failed: {}", error),
+            };
+        }
+    }
+
+    fn prepare_preset(&mut self) -> io::Result<()> {
+        let Some(preset) = self.presets.get(self.preset) else {
+            return Ok(());
+        };
+
+        let mut summaries = vec![];
+        if let Some(classes) = &self.classes {
+            summaries.push((
+                "Loaded".to_string(),
+                SlotSummary::from_unsaved(classes)?,
+            ));
+        }
+        let mut classes = self.classes.clone().unwrap_or_default();
+        preset.apply(&mut classes);
+        summaries.push((
+            preset.name.clone(),
+            SlotSummary::from_unsaved(&classes)?,
+        ));
+
+        self.pending_preset = Some((self.preset, summaries));
+        Ok(())
+    }
+
+    fn update_preset_confirmation(&mut self, ctx: &Context) {
+        let Some((index, summaries)) = &self.pending_preset else {
+            return;
+        };
+        let Some(preset) = self.presets.get(*index) else {
+            self.pending_preset = None;
+            return;
+        };
+
+        let mut confirmed = false;
+        let mut cancelled = false;
+
+        Window::new("Confirm preset")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.heading(format!("Replace loaded progress with {}", preset.name));
+                ui.label("Cybergrind bests and unused weapon variants are kept.");
+                ui.label("Unsaved changes will be lost.");
+                ui.add_space(5.0);
+
+                for (name, summary) in summaries {
+                    ui.label(format!("{}: {}", name, summary));
+                }
+
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    confirmed = ui.button("Confirm").clicked();
+                    cancelled = ui.button("Cancel").clicked();
+                });
+            });
+
+        if confirmed {
+            preset.apply(self.classes.get_or_insert_with(Classes::default));
+            self.save_enabled = self.save_path.is_some();
+            self.status = format!(
+                "Applied preset {}, save to write it to the slot",
+                preset.name
+            );
+            self.pending_preset = None;
+        } else if cancelled {
+            self.pending_preset = None;
+        }
+    }
+
+    fn update_slot_overview(&mut self, ctx: &Context) {
+        let Some(summaries) = &self.slot_overview else {
+            return;
+        };
+
+        let mut open = true;
+        let mut selected_slot = None;
+
+        Window::new("Slot overview")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                Grid::new("slot overview")
+                    .striped(true)
+                    .min_col_width(70.0)
+                    .show(ui, |ui| {
+                        ui.label("");
+                        for (slot, _) in summaries {
+                            ui.strong(&slot.name);
+                        }
+                        ui.end_row();
+
+                        overview_row(ui, "Files", summaries, |summary| {
+                            summary.files.to_string()
+                        });
+
+                        for difficulty in Difficulty::iter() {
+                            overview_row(
+                                ui,
+                                &format!("Levels completed ({})", difficulty),
+                                summaries,
+                                |summary| {
+                                    summary
+                                        .levels_completed_by_difficulty
+                                        .get(&difficulty)
+                                        .copied()
+                                        .unwrap_or_default()
+                                        .to_string()
+                                },
+                            );
+                        }
+
+                        overview_row(ui, "P-ranks", summaries, |summary| {
+                            summary.p_ranks.to_string()
+                        });
+                        overview_row(ui, "Secrets found", summaries, |summary| {
+                            summary.secrets_found.to_string()
+                        });
+                        overview_row(ui, "Primes beaten", summaries, |summary| {
+                            summary.primes_beaten.to_string()
+                        });
+                        overview_row(ui, "Weapons unlocked", summaries, |summary| {
+                            summary.weapons_unlocked.to_string()
+                        });
+                        overview_row(ui, "Money", summaries, |summary| {
+                            match summary.money.is_empty() {
+                                true => "-".to_string(),
+                                false => format!("{}P", summary.money),
+                            }
+                        });
+                        overview_row(ui, "Best cybergrind wave", summaries, |summary| {
+                            match summary.best_wave {
+                                Some(wave) => format!("{:.2}", wave),
+                                None => "-".to_string(),
+                            }
+                        });
+
+                        ui.label("");
+                        for (slot, summary) in summaries {
+                            if ui
+                                .add_enabled(summary.files > 0, Button::new("Open"))
+                                .clicked()
+                            {
+                                selected_slot = Some(slot.path.clone());
+                            }
+                        }
+                        ui.end_row();
+                    });
+            });
+
+        if let Some(path) = selected_slot {
+            if let Some(index) = self.slots.iter().position(|slot| slot.path == path) {
+                self.select_slot(index);
+                self.classes = Classes::load(&DirectoryStorage::new(&path));
+                self.save_enabled = self.classes.is_some();
+            }
+        }
+
+        if !open {
+            self.slot_overview = None;
+        }
+    }
+
+    fn update_stats(&mut self, ctx: &Context) {
+        let Some(classes) = &self.classes else {
+            return;
+        };
+
+        let stats = Stats::new(classes);
+
+        Window::new("Statistics")
+            .open(&mut self.show_stats)
+            .resizable(false)
+            .show(ctx, |ui| {
+                Grid::new("statistics")
+                    .striped(true)
+                    .min_col_width(70.0)
+                    .show(ui, |ui| {
+                        ui.label("");
+                        for column in COMPLETION_COLUMNS {
+                            ui.strong(column);
+                        }
+                        ui.end_row();
+
+                        for (label, completion) in stats.groups() {
+                            ui.label(label);
+                            for progress in completion.columns() {
+                                ui.label(progress.to_string());
+                            }
+                            ui.end_row();
+                        }
+                    });
+
+                ui.add_space(5.0);
+
+                Grid::new("statistics totals").show(ui, |ui| {
+                    for (label, progress) in stats.totals() {
+                        ui.label(label);
+                        ui.label(progress.to_string());
+                        ui.end_row();
+                    }
+                });
+
+                ui.add_space(5.0);
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label("Report path: ");
+                    if ui
+                        .add(TextEdit::singleline(&mut self.report_path).desired_width(300.0))
+                        .changed()
+                    {
+                        if let Some(format) = ReportFormat::from_path(Path::new(&self.report_path))
+                        {
+                            self.report_format = format;
+                        }
+                    }
+
+                    ComboBox::from_id_source("report format")
+                        .selected_text(self.report_format.to_string())
+                        .show_ui(ui, |ui| {
+                            for format in ReportFormat::iter() {
+                                ui.selectable_value(
+                                    &mut self.report_format,
+                                    format,
+                                    format.to_string(),
+                                );
+                            }
+                        });
+
+                    if ui
+                        .add_enabled(!self.report_path.is_empty(), Button::new("Export report"))
+                        .clicked()
+                    {
+                        let title = match &self.save_path {
+                            Some(save_path) => {
+                                format!("ULTRAKILL progress: {}", get_slot_name(save_path))
+                            }
+                            None => "ULTRAKILL progress".to_string(),
+                        };
+
+                        self.status = match write(
+                            &self.report_path,
+                            Report::new(classes, &title).render(self.report_format),
+                        ) {
+                            Ok(()) => format!("Exported report to {}", self.report_path),
+                            Err(error) => format!("Report export failed: {}", error),
+                        };
+                    }
+                });
+            });
+    }
+
+    fn update_rank_grid(&mut self, ctx: &Context) {
+        let Some(classes) = &mut self.classes else {
+            return;
+        };
+
+        let mut rank_edit = None;
+        let mut fill_row = None;
+        let mut fill_column = None;
+
+        Window::new("Rank grid")
+            .open(&mut self.show_rank_grid)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("Toggle MA to mark a clear as using major assists.");
+                ui.add_space(5.0);
+
+                ScrollArea::vertical().max_height(600.0).show(ui, |ui| {
+                    Grid::new("rank grid")
+                        .striped(true)
+                        .min_col_width(60.0)
+                        .show(ui, |ui| {
+                            ui.label("");
+                            for difficulty in Difficulty::iter() {
+                                ui.vertical(|ui| {
+                                    ui.strong(difficulty.to_string());
+                                    if let Some(rank) = rank_fill_menu(ui, "Fill") {
+                                        fill_column = Some((difficulty, rank));
+                                    }
+                                });
+                            }
+                            ui.end_row();
+
+                            for level in Level::iter() {
+                                let Some(level_data) = classes.levels.get_mut(&level) else {
+                                    continue;
+                                };
+
+                                ui.label(level.to_string());
+
+                                for difficulty in Difficulty::iter() {
+                                    let index = difficulty as usize;
+
+                                    ui.horizontal(|ui| {
+                                        let mut rank = *level_data.ranks.get(index)?;
+                                        if rank_combo_box(
+                                            ui,
+                                            format!("grid {} {}", level as u16, index),
+                                            &mut rank,
+                                            30.0,
+                                        ) {
+                                            rank_edit = Some((level, difficulty, rank));
+                                        }
+
+                                        if ui
+                                            .toggle_value(
+                                                level_data.major_assists.get_mut(index)?,
+                                                "MA",
+                                            )
+                                            .changed()
+                                        {
+                                            level_data.file_exists = true;
+                                        }
+
+                                        Some(())
+                                    });
+                                }
+
+                                if let Some(rank) = rank_fill_menu(ui, "Fill row") {
+                                    fill_row = Some((level, rank));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+
+        if let Some((level, difficulty, rank)) = rank_edit {
+            set_level_rank(classes, level, difficulty, rank);
+        }
+
+        if let Some((level, rank)) = fill_row {
+            for difficulty in Difficulty::iter() {
+                set_level_rank(classes, level, difficulty, rank);
+            }
+        }
+
+        if let Some((difficulty, rank)) = fill_column {
+            for level in Level::iter() {
+                set_level_rank(classes, level, difficulty, rank);
+            }
+        }
+    }
+
+    fn update_primes(&mut self, ctx: &Context) {
+        let Some(classes) = &mut self.classes else {
+            return;
+        };
+
+        let mut state_edit = None;
+        let mut rank_edit = None;
+        let mut secret_mission_edit = None;
+
+        Window::new("Prime sanctums")
+            .open(&mut self.show_primes)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("A prime's secret mission follows its best state on any difficulty.");
+                ui.label("Raising a secret mission raises the selected difficulty with it.");
+                ui.add_space(5.0);
+
+                Grid::new("primes").striped(true).show(ui, |ui| {
+                    ui.label("");
+                    ui.strong("Secret mission");
+                    for difficulty in Difficulty::iter() {
+                        ui.strong(difficulty.to_string());
+                    }
+                    ui.end_row();
+
+                    for prime in Prime::get_primes() {
+                        ui.label(prime.get_name());
+
+                        let mut secret_mission = prime.get_secret_mission(classes);
+                        if lockable_combo_box(
+                            ui,
+                            format!("prime {} secret mission", prime.index),
+                            &mut secret_mission,
+                        ) {
+                            secret_mission_edit = Some((prime, secret_mission));
+                        }
+
+                        for difficulty in Difficulty::iter() {
+                            ui.horizontal(|ui| {
+                                let mut state = prime.get_state(classes, difficulty)?;
+                                if lockable_combo_box(
+                                    ui,
+                                    format!("prime {} {} state", prime.index, difficulty),
+                                    &mut state,
+                                ) {
+                                    state_edit = Some((prime, difficulty, state));
+                                }
+
+                                let mut rank = prime.get_rank(classes, difficulty)?;
+                                if rank_combo_box(
+                                    ui,
+                                    format!("prime {} {} rank", prime.index, difficulty),
+                                    &mut rank,
+                                    30.0,
+                                ) {
+                                    rank_edit = Some((prime, difficulty, rank));
+                                }
+
+                                Some(())
+                            });
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+
+        if let Some((prime, state)) = secret_mission_edit {
+            prime.set_secret_mission(classes, self.difficulty, state);
+        }
+        if let Some((prime, difficulty, state)) = state_edit {
+            prime.set_state(classes, difficulty, state);
+        }
+        if let Some((prime, difficulty, rank)) = rank_edit {
+            prime.set_rank(classes, difficulty, rank);
+        }
+    }
+
+    /// Enemies are grouped by the layer they are first met in, with the ones
+    /// outside the campaign and any indexes the editor doesn't know at the end.
+    fn update_bestiary(&mut self, ctx: &Context) {
+        let Some(classes) = &mut self.classes else {
+            return;
+        };
+
+        let general = &classes.general;
+        let mut enemy_edits = vec![];
+        let mut unknown_edits = vec![];
+
+        let discovered = |enemies: &[EnemyType]| {
+            enemies
+                .iter()
+                .filter(|enemy| {
+                    general
+                        .enemies_discovered
+                        .get(enemy)
+                        .is_some_and(|state| *state != Lockable::Locked)
+                })
+                .count()
+        };
+        let secret_enemies: Vec<EnemyType> = EnemyType::iter()
+            .filter(|enemy| enemy.get_first_level().is_none())
+            .collect();
+        let all_enemies: Vec<EnemyType> = EnemyType::iter().collect();
+        let unknown_discovered = general
+            .unknown_enemies
+            .values()
+            .filter(|state| **state != Lockable::Locked)
+            .count();
+
+        Window::new("Bestiary")
+            .open(&mut self.show_bestiary)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} of {} enemies discovered",
+                    discovered(&all_enemies) + unknown_discovered,
+                    all_enemies.len() + general.unknown_enemies.len()
+                ));
+                ui.add_space(5.0);
+
+                ScrollArea::vertical().max_height(500.0).show(ui, |ui| {
+                    for act in Act::iter() {
+                        let enemies = Scope::Act(act).get_enemies();
+                        if enemies.is_empty() {
+                            continue;
+                        }
+
+                        CollapsingHeader::new(format!(
+                            "{} ({}/{})",
+                            act,
+                            discovered(&enemies),
+                            enemies.len()
+                        ))
+                        .id_source(format!("bestiary {}", act))
+                        .show(ui, |ui| {
+                            if let Some(state) = bestiary_fill_buttons(ui) {
+                                enemy_edits.extend(enemies.iter().map(|enemy| (*enemy, state)));
+                            }
+
+                            for layer in act.get_layers() {
+                                let enemies = Scope::Layer(*layer).get_enemies();
+                                if enemies.is_empty() {
+                                    continue;
+                                }
+
+                                ui.add_space(5.0);
+                                ui.strong(layer.to_string());
+
+                                for (label, boss) in [("Enemies", false), ("Bosses", true)] {
+                                    let enemies: Vec<EnemyType> = enemies
+                                        .iter()
+                                        .copied()
+                                        .filter(|enemy| enemy.is_boss() == boss)
+                                        .collect();
+                                    if enemies.is_empty() {
+                                        continue;
+                                    }
+
+                                    ui.label(label);
+                                    bestiary_grid(ui, general, &enemies, &mut enemy_edits);
+                                }
+                            }
+                        });
+                    }
+
+                    CollapsingHeader::new(format!(
+                        "SECRET ({}/{})",
+                        discovered(&secret_enemies) + unknown_discovered,
+                        secret_enemies.len() + general.unknown_enemies.len()
+                    ))
+                    .id_source("bestiary secret")
+                    .show(ui, |ui| {
+                        if let Some(state) = bestiary_fill_buttons(ui) {
+                            enemy_edits.extend(secret_enemies.iter().map(|enemy| (*enemy, state)));
+                            unknown_edits
+                                .extend(general.unknown_enemies.keys().map(|index| (*index, state)));
+                        }
+
+                        bestiary_grid(ui, general, &secret_enemies, &mut enemy_edits);
+
+                        if general.unknown_enemies.is_empty() {
+                            return;
+                        }
+
+                        ui.add_space(5.0);
+                        ui.label("Not known to the editor");
+                        Grid::new("bestiary unknown").show(ui, |ui| {
+                            for (index, state) in &general.unknown_enemies {
+                                ui.label(format!("Unknown enemy ({})", index));
+
+                                let mut state = *state;
+                                if bestiary_combo_box(
+                                    ui,
+                                    format!("unknown enemy {} state", index),
+                                    &mut state,
+                                ) {
+                                    unknown_edits.push((*index, state));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    });
+                });
+            });
+
+        let general = &mut classes.general;
+        for (enemy, state) in enemy_edits {
+            general.enemies_discovered.insert(enemy, state);
+            general.file_exists = true;
+        }
+        for (index, state) in unknown_edits {
+            general.unknown_enemies.insert(index, state);
+            general.file_exists = true;
+        }
+    }
+
+    fn export_archive(&self) -> Result<String, String> {
+        let save_path = self
+            .save_path
+            .as_ref()
+            .ok_or_else(|| "no save path".to_string())?;
+        let file = File::create(&self.archive_path).map_err(|error| error.to_string())?;
+        let slot_name = save_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let manifest = export_slot(&DirectoryStorage::new(save_path), &slot_name, file)
+            .map_err(|error| error.to_string())?;
+
+        Ok(format!(
+            "Exported {} files to {}",
+            manifest.files.len(),
+            self.archive_path
+        ))
+    }
+
+    /// Opens the archive and asks for confirmation before it replaces the
+    /// slot.
+    fn prepare_import(&mut self) -> Result<(), String> {
+        let save_path = self
+            .save_path
+            .as_ref()
+            .ok_or_else(|| "no save path".to_string())?;
+        let file = File::open(&self.archive_path).map_err(|error| error.to_string())?;
+        let slot_archive = SlotArchive::open(file).map_err(|error| error.to_string())?;
+
+        let summaries = vec![
+            (
+                format!("Archive of {}", slot_archive.manifest.source_slot),
+                slot_archive
+                    .get_summary()
+                    .map_err(|error| error.to_string())?,
+            ),
+            (
+                get_slot_name(save_path),
+                SlotSummary::load(&DirectoryStorage::new(save_path))
+                    .map_err(|error| error.to_string())?,
+            ),
+        ];
+
+        self.pending_import = Some((slot_archive, summaries));
+        Ok(())
+    }
+
+    fn import_archive(&mut self, slot_archive: &SlotArchive) -> Result<String, String> {
+        let save_path = self
+            .save_path
+            .as_ref()
+            .ok_or_else(|| "no save path".to_string())?;
+        let mut storage = DirectoryStorage::new(save_path);
+
+        slot_archive
+            .import_into(&mut storage)
+            .map_err(|error| error.to_string())?;
+
+        self.classes = Classes::load(&storage);
+        self.save_enabled = self.classes.is_some();
+
+        Ok(format!(
+            "Imported {} files from {}",
+            slot_archive.manifest.files.len(),
+            slot_archive.manifest.source_slot
+        ))
+    }
+
+    fn update_single_level(&mut self, ui: &mut Ui, level: &Level) -> Option<()> {
+        let difficulty = self.difficulty as usize;
+
+        let classes = self.classes.as_mut()?;
+        let prime = Prime::from_level(level);
+
+        let mut rank_edit = None;
+        ui.horizontal(|ui| {
+            ui.label("Rank: ");
+
+            let mut rank = *classes.levels.get(level)?.ranks.get(difficulty)?;
+            if rank_combo_box(ui, format!("level {} rank", *level as u16), &mut rank, 70.0) {
+                rank_edit = Some(rank);
+            }
+
+            Some(())
+        });
+
+        if let Some(rank) = rank_edit {
+            set_level_rank(classes, *level, self.difficulty, rank);
+        }
+
+        if let Some(prime) = prime {
+            let mut state_edit = None;
+            ui.horizontal(|ui| {
+                ui.label("State: ");
+
+                let mut state = prime.get_state(classes, self.difficulty)?;
+                if lockable_combo_box(ui, format!("level {} state", *level as u16), &mut state) {
+                    state_edit = Some(state);
+                }
+
+                Some(())
+            });
+
+            if let Some(state) = state_edit {
+                prime.set_state(classes, self.difficulty, state);
+            }
+        }
+
+        let level_data = classes.levels.get_mut(level)?;
+
+        if level_data.secrets_found.len() > 0 {
+            ui.horizontal(|ui| {
+                ui.label("Secrets found: ");
+                for secret in &mut level_data.secrets_found {
+                    ui.checkbox(secret, "");
+                }
+            });
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Challenge completed:");
+            ui.checkbox(&mut level_data.challenge, "");
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Used major assists:");
+            ui.checkbox(
+                level_data.major_assists.get_mut(difficulty)?,
+                "",
+            );
+
+            Some(())
+        });
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !level_data.file_exists,
+                    Button::new("Create file"),
+                )
+                .clicked()
+            {
+                level_data.file_exists = true
+            }
+
+            if ui
+                .add_enabled(
+                    level_data.file_exists,
+                    Button::new("Delete file"),
+                )
+                .clicked()
+            {
+                level_data.file_exists = false
+            }
+        });
+
+        Some(())
+    }
+
+    fn update_secret_level(&mut self, ui: &mut Ui, secret_level: &SecretLevel) {
+        if !secret_level.is_prime() && !secret_level.is_placeholder() {
+            ui.collapsing(secret_level.to_string(), |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("State: ");
+
+                    let state = self
+                        .classes
+                        .as_mut()?
+                        .general
+                        .secret_missions
+                        .get_mut(&secret_level)?;
+
+                    ComboBox::from_id_source(format!(
+                        "secret_level {} state",
+                        *secret_level as u8
+                    ))
+                    .selected_text(state.to_string())
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(state, Lockable::Locked, "Locked");
+                        ui.selectable_value(state, Lockable::Unlocked, "Unlocked");
+                        ui.selectable_value(state, Lockable::Completed, "Completed");
+                    });
+
+                    Some(())
+                });
+            });
+        }
+    }
+
+    fn update_campaign(&mut self, ui: &mut Ui) -> Option<()> {
+        let difficulty_data = self
+            .classes
+            .as_mut()?
+            .difficulty
+            .get_mut(&self.difficulty)?;
+
+        ui.horizontal(|ui| {
+            ui.label("Next level: ");
+
+            ComboBox::from_id_source("campaign position")
+                .selected_text(difficulty_data.current_level.to_string())
+                .width(250.0)
+                .show_ui(ui, |ui| {
+                    for position in CampaignPosition::get_positions() {
+                        if ui
+                            .selectable_value(
+                                &mut difficulty_data.current_level,
+                                position,
+                                position.to_string(),
+                            )
+                            .clicked()
+                        {
+                            difficulty_data.file_exists = true;
+                        }
+                    }
+                });
+        });
+
+        let reachable: Vec<String> = Level::iter()
+            .filter(|level| difficulty_data.current_level.is_reachable(level))
+            .map(|level| level.to_string().split(':').next().unwrap_or_default().to_string())
+            .collect();
+
+        ui.label(format!(
+            "Reachable: {} to {} ({} levels)",
+            reachable.first()?,
+            reachable.last()?,
+            reachable.len()
+        ));
+
+        Some(())
+    }
+
+    /// Each level in Limbo holds one of the switches that open 1-S.
+    fn update_limbo_switches(&mut self, ui: &mut Ui) {
+        ui.collapsing("LIMBO SWITCHES", |ui| {
+            let general = &mut self.classes.as_mut()?.general;
+            let secret_level = SecretLevel::TheWitless;
+
+            for (i, (level, switch)) in Layer::Limbo
+                .get_levels()
+                .iter()
+                .zip(general.limbo_switches.iter_mut())
+                .enumerate()
+            {
+                ui.checkbox(switch, format!("Switch {}: {}", i + 1, level));
+            }
+
+            let all_set = general.limbo_switches.iter().all(|set| *set);
+            let state = general.secret_missions.get_mut(&secret_level)?;
+
+            match (all_set, *state) {
+                (true, Lockable::Locked) => {
+                    ui.colored_label(
+                        Color32::YELLOW,
+                        format!("Every switch is set but {} is locked", secret_level),
+                    );
+                    if ui.button(format!("Unlock {}", secret_level)).clicked() {
+                        *state = Lockable::Unlocked;
+                    }
+                }
+                (false, Lockable::Unlocked | Lockable::Completed) => {
+                    ui.colored_label(
+                        Color32::YELLOW,
+                        format!("{} is {} but not every switch is set", secret_level, state),
+                    );
+                    if ui.button("Set every switch").clicked() {
+                        general.limbo_switches.fill(true);
+                    }
+                }
+                _ => {}
+            }
+
+            Some(())
+        });
+    }
+
+    fn update_levels(&mut self, ui: &mut Ui) {
+        ui.group(|ui| {
+            let mut bulk_edit = None;
+            let difficulty = self.difficulty;
+
+            ui.heading("Levels").context_menu(|ui| {
+                bulk_edit = bulk_menu(ui, Scope::All, difficulty, &mut self.bulk_all_difficulties);
+            });
+            ui.add_space(10.0);
+
+            self.update_campaign(ui);
+            ui.add_space(5.0);
+
+            let position = self
+                .classes
+                .as_ref()
+                .and_then(|classes| classes.difficulty.get(&difficulty))
+                .map(|difficulty_data| difficulty_data.current_level);
+
+            ScrollArea::vertical().show(ui, |ui| {
+                ui.set_height(ui.available_height());
+                ui.set_width(350.0);
+                for act in Act::iter() {
+                    ui.collapsing(act.to_string(), |ui| {
+                        for layer in act.get_layers() {
+                            ui.collapsing(layer.to_string(), |ui| {
+                                for level in layer.get_levels() {
+                                    let mut text = RichText::new(level.to_string());
+                                    if position.is_some_and(|position| {
+                                        !level.is_prime() && !position.is_reachable(level)
+                                    }) {
+                                        text = text.weak();
+                                    }
+
+                                    ui.collapsing(text, |ui| {
+                                        self.update_single_level(ui, level);
+                                    })
+                                    .header_response
+                                    .context_menu(|ui| {
+                                        bulk_edit = bulk_menu(
+                                            ui,
+                                            Scope::Level(*level),
+                                            difficulty,
+                                            &mut self.bulk_all_difficulties,
+                                        );
+                                    });
+                                }
+                                if *layer == Layer::Limbo {
+                                    self.update_limbo_switches(ui);
+                                }
+                                self.update_secret_level(ui, &layer.get_secret_level());
+                            })
+                            .header_response
+                            .context_menu(|ui| {
+                                bulk_edit = bulk_menu(
+                                    ui,
+                                    Scope::Layer(*layer),
+                                    difficulty,
+                                    &mut self.bulk_all_difficulties,
+                                );
+                            });
+                        }
+                    })
+                    .header_response
+                    .context_menu(|ui| {
+                        bulk_edit = bulk_menu(
+                            ui,
+                            Scope::Act(act),
+                            difficulty,
+                            &mut self.bulk_all_difficulties,
+                        );
+                    });
+                }
+            });
+
+            if let (Some(bulk_edit), Some(classes)) = (bulk_edit, &mut self.classes) {
+                let count = bulk_edit.apply(classes);
+                self.status = format!("{} ({} changed)", bulk_edit, count);
+            }
+        });
+    }
+
+    fn update_general(&mut self, ui: &mut Ui) {
+        ui.group(|ui| {
+            let available_width = ui.available_width();
+            ui.heading("General data");
+            ui.add_space(10.0);
+
+            let classes = self.classes.as_mut()?;
+
+            ui.horizontal(|ui| {
+                ui.label("Money:");
+                if ui
+                    .text_edit_singleline(&mut classes.general.money)
+                    .changed()
+                {
+                    validate_u32(&mut classes.general.money);
+                }
+            });
+
+            ui.horizontal(|ui| {
                 ui.label("Intro seen:");
                 ui.checkbox(&mut classes.general.intro_seen, "");
             });
@@ -355,230 +1712,680 @@ impl<'a> SaveEditorApp {
                 ui.checkbox(&mut classes.general.tutorial_beat, "");
             });
 
-            ui.horizontal(|ui| {
-                ui.label("Clash mode unlocked:");
-                ui.checkbox(
-                    &mut classes.general.clash_mode_unlocked,
-                    "",
-                );
-            });
+            ui.horizontal(|ui| {
+                ui.label("Clash mode unlocked:");
+                ui.checkbox(
+                    &mut classes.general.clash_mode_unlocked,
+                    "",
+                );
+            });
+
+            ui.collapsing("Unlockables", |ui| {
+                ui.set_width(150.0);
+                ui.columns(2, |column| {
+                    column[0].add_space(1.0);
+                    for (unlockable_type, found) in classes.general.unlockables_found.iter_mut() {
+                        column[0].label(unlockable_type.to_string() + " Found:");
+                        column[0].add_space(4.0);
+                        column[1].checkbox(found, "");
+                    }
+                });
+            });
+
+            ui.collapsing("Weapons", |ui| {
+                ui.checkbox(&mut self.show_unused_weapons, "Show unused variants");
+                let buyable = get_buyable(&classes.general);
+                ui.label(format!(
+                    "Spent {}P in the shop, {}P left to spend on {} variants",
+                    get_spent(&classes.general),
+                    buyable
+                        .iter()
+                        .filter_map(|variant| variant.get_price())
+                        .sum::<u32>(),
+                    buyable.len()
+                ));
+                ui.set_max_height(ui.available_height() - 55.0);
+                ScrollArea::vertical().show(ui, |ui| {
+                    for weapon in WeaponType::iter() {
+                        if weapon.is_unused() && !self.show_unused_weapons {
+                            continue;
+                        }
+
+                        ui.collapsing(weapon.to_string() + "s", |ui| {
+                            ui.set_width(match weapon {
+                                WeaponType::Revolver => 385.0,
+                                WeaponType::Shotgun => 385.0,
+                                WeaponType::Nailgun => 350.0,
+                                WeaponType::Railgun => 350.0,
+                                WeaponType::RocketLauncher => 475.0,
+                                WeaponType::Arm => 300.0,
+                                WeaponType::Beam => 300.0,
+                            });
+
+                            if ui.button("Unlock everything").clicked() {
+                                unlock_weapon(&mut classes.general, &weapon);
+                            }
+
+                            let base_owned = match weapon.get_base_variant() {
+                                Some(base) => {
+                                    classes.general.unlocked_weapons.get(&base) == Some(&true)
+                                }
+                                None => true,
+                            };
+
+                            ui.columns(2, |column| {
+                                column[0].add_space(1.0);
+                                if let Some(customizable) = weapon.get_customizable() {
+                                    column[0].with_layout(
+                                        Layout::right_to_left(Align::Min),
+                                        |ui| ui.label("Customizable:"),
+                                    );
+                                    column[0].add_space(3.625);
+
+                                    // Customization is bought for a weapon already owned.
+                                    let value = classes
+                                        .general
+                                        .weapons_customizable
+                                        .get_mut(&customizable)?;
+                                    column[1].add_enabled(
+                                        base_owned || *value,
+                                        Checkbox::new(value, ""),
+                                    );
+                                }
+
+                                for variant in weapon.get_unlockable_variants() {
+                                    if variant.is_unused() && !self.show_unused_weapons {
+                                        continue;
+                                    }
+
+                                    let mut text = RichText::new(variant.to_string() + " Unlocked:");
+                                    if variant.is_unused() {
+                                        text = text.weak();
+                                    }
+
+                                    let source = match variant.get_price() {
+                                        Some(price) => format!(
+                                            "{} for {}P. Right-click to buy or refund it.",
+                                            variant.get_source(),
+                                            price
+                                        ),
+                                        None => variant.get_source().to_string(),
+                                    };
+
+                                    column[0].with_layout(
+                                        Layout::right_to_left(Align::Min),
+                                        |ui| ui.label(text).on_hover_text(&source),
+                                    );
+                                    column[0].add_space(3.625);
+                                    let response = column[1]
+                                        .checkbox(
+                                            classes.general.unlocked_weapons.get_mut(variant)?,
+                                            "",
+                                        )
+                                        .on_hover_text(&source);
+
+                                    if variant.get_price().is_some() {
+                                        response.context_menu(|ui| {
+                                            let result = if ui.button("Buy").clicked() {
+                                                Some(buy(&mut classes.general, variant))
+                                            } else if ui.button("Refund").clicked() {
+                                                Some(refund(&mut classes.general, variant))
+                                            } else {
+                                                None
+                                            };
+
+                                            if let Some(result) = result {
+                                                self.status = match result {
+                                                    Ok(()) => format!(
+                                                        "Money left: {}P",
+                                                        classes.general.money
+                                                    ),
+                                                    Err(error) => error.to_string(),
+                                                };
+                                                ui.close_menu();
+                                            }
+                                        });
+                                    }
+                                }
+
+                                Some(())
+                            });
+
+                            for conflict in get_conflicts(&classes.general, &weapon) {
+                                ui.colored_label(Color32::YELLOW, conflict.to_string());
+                            }
+                        });
+                    }
+                });
+            });
+
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(
+                        !classes.general.file_exists,
+                        Button::new("Create file"),
+                    )
+                    .clicked()
+                {
+                    classes.general.file_exists = true
+                }
+
+                if ui
+                    .add_enabled(
+                        classes.general.file_exists,
+                        Button::new("Delete file"),
+                    )
+                    .clicked()
+                {
+                    classes.general.file_exists = false
+                }
+            });
+
+            ui.set_height(ui.available_height());
+            ui.set_width(available_width);
+
+            Some(())
+        });
+    }
+
+    fn refresh_patterns(&mut self) {
+        match list_patterns(Path::new(&self.patterns_dir)) {
+            Ok(files) => self.pattern_files = files,
+            Err(error) => {
+                self.pattern_files.clear();
+                self.status = format!("Listing patterns failed: {}", error);
+            }
+        }
+    }
+
+    fn update_patterns(&mut self, ctx: &Context) {
+        let mut open = self.show_patterns;
+        let mut refresh = false;
+        let mut load = None;
+        let mut save = false;
+
+        Window::new("Cybergrind patterns")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Folder:");
+                    ui.add(TextEdit::singleline(&mut self.patterns_dir).desired_width(300.0));
+                    if ui.button("Refresh").clicked() {
+                        refresh = true;
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ComboBox::from_id_source("pattern file")
+                        .selected_text("Open...")
+                        .width(200.0)
+                        .show_ui(ui, |ui| {
+                            for path in &self.pattern_files {
+                                let name = path
+                                    .file_stem()
+                                    .map(|name| name.to_string_lossy().to_string())
+                                    .unwrap_or_default();
+                                if ui.selectable_label(false, name).clicked() {
+                                    load = Some(path.clone());
+                                }
+                            }
+                        });
+
+                    if ui.button("New").clicked() {
+                        self.pattern = Some(Pattern::default());
+                        self.pattern_name = "New pattern".to_string();
+                    }
+                });
+
+                let Some(pattern) = &mut self.pattern else {
+                    return;
+                };
+
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    ui.add(TextEdit::singleline(&mut self.pattern_name).desired_width(200.0));
+                    if ui
+                        .add_enabled(!self.pattern_name.is_empty(), Button::new("Save"))
+                        .clicked()
+                    {
+                        save = true;
+                    }
+                });
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.pattern_paint_prefab, false, "Height");
+                    ui.add(DragValue::new(&mut self.pattern_height).clamp_range(-50..=50));
+                    ui.radio_value(&mut self.pattern_paint_prefab, true, "Prefab");
+                    ComboBox::from_id_source("pattern prefab")
+                        .selected_text(self.pattern_prefab.to_string())
+                        .show_ui(ui, |ui| {
+                            for prefab in Prefab::iter() {
+                                ui.selectable_value(
+                                    &mut self.pattern_prefab,
+                                    prefab,
+                                    prefab.to_string(),
+                                );
+                            }
+                        });
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button("Mirror left to right").clicked() {
+                        pattern.mirror(Mirror::LeftToRight);
+                    }
+                    if ui.button("Mirror top to bottom").clicked() {
+                        pattern.mirror(Mirror::TopToBottom);
+                    }
+                });
+                ui.add_space(5.0);
+
+                // Cells are painted while the mouse is held down over them, so
+                // whole areas can be dragged over, but only if the drag started
+                // on a cell.
+                if !ui.input(|input| input.pointer.primary_down()) {
+                    self.pattern_painting = false;
+                }
+                Grid::new("pattern")
+                    .spacing(vec2(2.0, 2.0))
+                    .show(ui, |ui| {
+                        for row in 0..PATTERN_SIZE {
+                            for column in 0..PATTERN_SIZE {
+                                let height = pattern.heights[row][column];
+                                let prefab = pattern.prefabs[row][column];
+                                let text = match prefab {
+                                    Prefab::None => height.to_string(),
+                                    prefab => format!("{}{}", height, prefab.to_char()),
+                                };
+
+                                let response = ui
+                                    .add(
+                                        Button::new(
+                                            RichText::new(text)
+                                                .small()
+                                                .color(prefab_color(&prefab)),
+                                        )
+                                        .fill(height_color(height))
+                                        .min_size(vec2(28.0, 28.0)),
+                                    )
+                                    .on_hover_text(format!("Height {}, {}", height, prefab));
+
+                                if response.is_pointer_button_down_on() {
+                                    self.pattern_painting = true;
+                                }
+
+                                if response.clicked()
+                                    || (self.pattern_painting
+                                        && ui.rect_contains_pointer(response.rect))
+                                {
+                                    match self.pattern_paint_prefab {
+                                        true => pattern.prefabs[row][column] = self.pattern_prefab,
+                                        false => pattern.heights[row][column] = self.pattern_height,
+                                    }
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+
+                ui.add_space(5.0);
+                for problem in pattern.validate() {
+                    ui.colored_label(Color32::YELLOW, problem);
+                }
+            });
+
+        self.show_patterns = open;
+
+        if refresh {
+            self.refresh_patterns();
+        }
+
+        if let Some(path) = load {
+            match Pattern::load(&path) {
+                Ok(pattern) => {
+                    self.pattern = Some(pattern);
+                    self.pattern_name = path
+                        .file_stem()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                }
+                Err(error) => self.status = format!("Loading pattern failed: {}", error),
+            }
+        }
+
+        if save {
+            match get_pattern_path(Path::new(&self.patterns_dir), &self.pattern_name) {
+                Ok(path) => match path.try_exists() {
+                    Ok(true) => self.pending_pattern_overwrite = Some(path),
+                    Ok(false) => self.save_pattern(&path),
+                    Err(error) => self.status = format!("Saving pattern failed: {}", error),
+                },
+                Err(error) => self.status = format!("Saving pattern failed: {}", error),
+            }
+        }
+    }
+
+    fn save_pattern(&mut self, path: &Path) {
+        let Some(pattern) = &self.pattern else {
+            return;
+        };
+
+        match pattern.save(path) {
+            Ok(()) => {
+                self.status = format!("Saved pattern to {}", path.to_string_lossy());
+                self.refresh_patterns();
+            }
+            Err(error) => self.status = format!("Saving pattern failed: {}", error),
+        }
+    }
+
+    fn update_pattern_overwrite_confirmation(&mut self, ctx: &Context) {
+        let Some(path) = &self.pending_pattern_overwrite else {
+            return;
+        };
+
+        let mut confirmed = false;
+        let mut cancelled = false;
 
-            ui.collapsing("Unlockables", |ui| {
-                ui.set_width(150.0);
-                ui.columns(2, |column| {
-                    column[0].add_space(1.0);
-                    for (unlockable_type, found) in classes.general.unlockables_found.iter_mut() {
-                        column[0].label(unlockable_type.to_string() + " Found:");
-                        column[0].add_space(4.0);
-                        column[1].checkbox(found, "");
-                    }
+        Window::new("Confirm overwrite")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.heading(format!("Overwrite {}", path.to_string_lossy()));
+                ui.label("A pattern with this name already exists.");
+                ui.add_space(5.0);
+
+                ui.horizontal(|ui| {
+                    confirmed = ui.button("Overwrite").clicked();
+                    cancelled = ui.button("Cancel").clicked();
                 });
             });
 
-            ui.collapsing("Weapons", |ui| {
-                ui.set_max_height(ui.available_height() - 55.0);
-                ScrollArea::vertical().show(ui, |ui| {
-                    for weapon in WeaponType::iter() {
-                        ui.collapsing(weapon.to_string() + "s", |ui| {
-                            ui.set_width(match weapon {
-                                WeaponType::Revolver => 385.0,
-                                WeaponType::Shotgun => 385.0,
-                                WeaponType::Nailgun => 350.0,
-                                WeaponType::Railgun => 350.0,
-                                WeaponType::RocketLauncher => 475.0,
-                                WeaponType::Arm => 300.0,
-                            });
+        if confirmed {
+            let path = self.pending_pattern_overwrite.take().unwrap();
+            self.save_pattern(&path);
+        } else if cancelled {
+            self.pending_pattern_overwrite = None;
+        }
+    }
 
-                            ui.columns(2, |column| {
-                                column[0].add_space(1.0);
-                                if let Some(customizable) = weapon.get_customizable() {
-                                    column[0].with_layout(
-                                        Layout::right_to_left(Align::Min),
-                                        |ui| ui.label("Customizable:"),
-                                    );
-                                    column[0].add_space(3.625);
-                                    column[1].checkbox(
-                                        classes
-                                            .general
-                                            .weapons_customizable
-                                            .get_mut(&customizable)?,
-                                        "",
-                                    );
-                                }
+    fn refresh_sandbox(&mut self) {
+        match list_sandbox_saves(Path::new(&self.sandbox_dir)) {
+            Ok(files) => self.sandbox_files = files,
+            Err(error) => {
+                self.sandbox_files.clear();
+                self.status = format!("Listing sandbox saves failed: {}", error);
+            }
+        }
+    }
 
-                                for variant in weapon.get_unlockable_variants() {
-                                    column[0].with_layout(
-                                        Layout::right_to_left(Align::Min),
-                                        |ui| ui.label(variant.to_string() + " Unlocked:"),
-                                    );
-                                    column[0].add_space(3.625);
-                                    column[1].checkbox(
-                                        classes.general.unlocked_weapons.get_mut(&variant)?,
-                                        "",
-                                    );
-                                }
+    fn select_sandbox(&mut self, path: PathBuf) {
+        match SandboxSave::load(&path) {
+            Ok(sandbox) => {
+                self.sandbox = Some(sandbox);
+                self.sandbox_name = get_save_name(&path);
+                self.sandbox_path = Some(path);
+            }
+            Err(error) => self.status = format!("Loading sandbox save failed: {}", error),
+        }
+    }
 
-                                Some(())
-                            });
-                        });
+    fn update_sandbox(&mut self, ctx: &Context) {
+        let mut open = self.show_sandbox;
+        let mut refresh = false;
+        let mut select = None;
+        let mut rename = false;
+        let mut duplicate = false;
+        let mut delete = false;
+        let mut save = false;
+
+        Window::new("Sandbox saves")
+            .open(&mut open)
+            .default_height(500.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Folder:");
+                    ui.add(TextEdit::singleline(&mut self.sandbox_dir).desired_width(300.0));
+                    if ui.button("Refresh").clicked() {
+                        refresh = true;
                     }
                 });
-            });
 
-            ui.collapsing("Enemies", |ui| {
-                ui.set_width(400.0);
-                ui.set_height(ui.available_height() - 35.0);
-                ScrollArea::vertical().show(ui, |ui| {
-                    ui.columns(2, |column| {
-                        column[0].add_space(1.0);
-                        for (enemy_type, state) in classes.general.enemies_discovered.iter_mut() {
-                            column[0].with_layout(
-                                Layout::right_to_left(Align::Min),
-                                |ui| ui.label(enemy_type.to_string() + ":"),
-                            );
-                            column[0].add_space(3.625);
-
-                            ComboBox::from_id_source(format!(
-                                "enemy {} state",
-                                *enemy_type as u8
-                            ))
-                            .width(140.0)
-                            .selected_text(match state {
-                                Lockable::Locked => "Undiscovered",
-                                Lockable::Unlocked => "Partially Discovered",
-                                Lockable::Completed => "Fully Discovered",
-                            })
-                            .show_ui(&mut column[1], |ui| {
-                                ui.selectable_value(state, Lockable::Locked, "Undiscovered");
-                                ui.selectable_value(
-                                    state,
-                                    Lockable::Unlocked,
-                                    "Partially Discovered",
-                                );
-                                ui.selectable_value(
-                                    state,
-                                    Lockable::Completed,
-                                    "Fully Discovered",
-                                );
-                            });
+                ComboBox::from_id_source("sandbox file")
+                    .selected_text(match &self.sandbox_path {
+                        Some(path) => get_save_name(path),
+                        None => "Open...".to_string(),
+                    })
+                    .width(200.0)
+                    .show_ui(ui, |ui| {
+                        for path in &self.sandbox_files {
+                            let selected = self.sandbox_path.as_ref() == Some(path);
+                            if ui.selectable_label(selected, get_save_name(path)).clicked() {
+                                select = Some(path.clone());
+                            }
+                        }
+                    });
+
+                let Some(sandbox) = &mut self.sandbox else {
+                    return;
+                };
+
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    ui.add(TextEdit::singleline(&mut self.sandbox_name).desired_width(200.0));
+                    if ui.button("Rename").clicked() {
+                        rename = true;
+                    }
+                    if ui.button("Duplicate").clicked() {
+                        duplicate = true;
+                    }
+                    ui.menu_button("Delete", |ui| {
+                        if ui.button("Delete this save").clicked() {
+                            delete = true;
+                            ui.close_menu();
                         }
                     });
                 });
-            });
+                ui.separator();
 
-            ui.add_space(10.0);
+                ScrollArea::vertical().show(ui, |ui| {
+                    for category in SandboxCategory::iter() {
+                        let objects = sandbox.get_objects_mut(category);
+                        CollapsingHeader::new(format!("{} ({})", category, objects.len()))
+                            .id_source(format!("sandbox {}", category))
+                            .show(ui, |ui| sandbox_grid(ui, category, objects));
+                    }
+                });
 
-            ui.horizontal(|ui| {
-                if ui
-                    .add_enabled(
-                        !classes.general.file_exists,
-                        Button::new("Create file"),
-                    )
-                    .clicked()
-                {
-                    classes.general.file_exists = true
+                ui.separator();
+                if ui.button("Save changes").clicked() {
+                    save = true;
                 }
+            });
 
-                if ui
-                    .add_enabled(
-                        classes.general.file_exists,
-                        Button::new("Delete file"),
-                    )
-                    .clicked()
-                {
-                    classes.general.file_exists = false
+        self.show_sandbox = open;
+
+        if refresh {
+            self.refresh_sandbox();
+        }
+
+        if let Some(path) = select {
+            self.select_sandbox(path);
+        }
+
+        let Some(path) = self.sandbox_path.clone() else {
+            return;
+        };
+
+        if save {
+            if let Some(sandbox) = &self.sandbox {
+                self.status = match sandbox.save(&path) {
+                    Ok(()) => format!("Saved {}", get_save_name(&path)),
+                    Err(error) => format!("Saving sandbox save failed: {}", error),
+                };
+            }
+        }
+
+        // The save in memory is kept as it is, so unsaved changes carry over
+        // to the renamed save or the copy.
+        if rename {
+            match rename_save(&path, &self.sandbox_name) {
+                Ok(new_path) => {
+                    self.status = format!("Renamed to {}", get_save_name(&new_path));
+                    self.sandbox_path = Some(new_path);
+                    self.refresh_sandbox();
                 }
-            });
+                Err(error) => self.status = format!("Renaming sandbox save failed: {}", error),
+            }
+        }
 
-            ui.set_height(ui.available_height());
-            ui.set_width(available_width);
+        if duplicate {
+            match duplicate_save(&path, &self.sandbox_name) {
+                Ok(new_path) => {
+                    self.status = format!("Duplicated to {}", get_save_name(&new_path));
+                    self.sandbox_path = Some(new_path);
+                    self.refresh_sandbox();
+                }
+                Err(error) => self.status = format!("Duplicating sandbox save failed: {}", error),
+            }
+        }
 
-            Some(())
-        });
+        if delete {
+            match delete_save(&path) {
+                Ok(()) => {
+                    self.sandbox = None;
+                    self.sandbox_path = None;
+                    self.sandbox_name.clear();
+                    self.refresh_sandbox();
+                }
+                Err(error) => self.status = format!("Deleting sandbox save failed: {}", error),
+            }
+        }
     }
 
     fn update_cybergrind(&mut self, ui: &mut Ui) {
-        let difficulty = self.difficulty as usize;
+        let selected = self.difficulty as usize;
 
         ui.group(|ui| {
-            ui.heading("Cybergrind");
+            ui.horizontal(|ui| {
+                ui.heading("Cybergrind");
+                if ui.button("Patterns").clicked() {
+                    self.show_patterns = !self.show_patterns;
+                    if self.show_patterns {
+                        self.refresh_patterns();
+                    }
+                }
+            });
             ui.add_space(10.0);
 
             let classes = self.classes.as_mut()?;
-            let wave = classes.cybergrind.waves.get_mut(difficulty)?;
-            let kills = classes.cybergrind.kills.get_mut(difficulty)?;
-            let style = classes.cybergrind.style.get_mut(difficulty)?;
-            let time = classes.cybergrind.times.get_mut(difficulty)?;
+            let cybergrind = &mut classes.cybergrind;
+            let mut wave_changed = false;
 
-            let available_width = ui.available_width();
+            Grid::new("cybergrind").striped(true).show(ui, |ui| {
+                for header in ["Difficulty", "Wave", "", "Kills", "Style", "Time (mm:ss.fff)"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
 
-            ui.set_width(200.0);
-            ui.columns(2, |column| {
-                column[0].add_space(1.0);
-                column[0].with_layout(
-                    Layout::right_to_left(Align::Min),
-                    |ui| ui.label("Wave: "),
-                );
+                for i in 0..cybergrind.waves.len() {
+                    let name = match Difficulty::from_repr(i as u8) {
+                        Some(difficulty) => difficulty.to_string(),
+                        None => format!("Difficulty {}", i),
+                    };
+                    match i == selected {
+                        true => ui.strong(name),
+                        false => ui.label(name),
+                    };
 
-                if column[1].text_edit_singleline(wave).changed() {
-                    validate_f32(wave);
-                }
+                    let wave = cybergrind.waves.get_mut(i)?;
+                    if ui
+                        .add(TextEdit::singleline(wave).desired_width(60.0))
+                        .changed()
+                    {
+                        validate_f32(wave);
+                        wave_changed = true;
+                    }
 
-                column[0].add_space(4.0);
-                column[0].with_layout(
-                    Layout::right_to_left(Align::Min),
-                    |ui| ui.label("Kills: "),
-                );
+                    let (reached, progress) = split_wave(wave.parse().unwrap_or_default());
+                    ui.weak(format!("wave {} + {:.0}%", reached, progress));
 
-                if column[1].text_edit_singleline(kills).changed() {
-                    validate_u32(kills);
-                }
+                    let kills = cybergrind.kills.get_mut(i)?;
+                    if ui
+                        .add(TextEdit::singleline(kills).desired_width(60.0))
+                        .changed()
+                    {
+                        validate_u32(kills);
+                    }
 
-                column[0].add_space(4.0);
-                column[0].with_layout(
-                    Layout::right_to_left(Align::Min),
-                    |ui| ui.label("Style: "),
-                );
+                    let style = cybergrind.style.get_mut(i)?;
+                    if ui
+                        .add(TextEdit::singleline(style).desired_width(60.0))
+                        .changed()
+                    {
+                        validate_u32(style);
+                    }
+
+                    // The typed text is kept while editing, since a partly typed time
+                    // can't be parsed yet.
+                    let time = cybergrind.times.get_mut(i)?;
+                    let mut text = match &self.cybergrind_time_edit {
+                        Some((index, text)) if *index == i => text.clone(),
+                        _ => format_time(time.parse().unwrap_or_default()),
+                    };
+                    let response = ui.add(TextEdit::singleline(&mut text).desired_width(80.0));
 
-                if column[1].text_edit_singleline(style).changed() {
-                    validate_u32(style);
+                    if response.changed() {
+                        if let Some(seconds) = parse_time(&text) {
+                            *time = seconds.to_string();
+                        }
+                        self.cybergrind_time_edit = Some((i, text));
+                    }
+                    if response.lost_focus() {
+                        self.cybergrind_time_edit = None;
+                    }
+                    ui.end_row();
                 }
 
-                column[0].add_space(4.0);
-                column[0].with_layout(
-                    Layout::right_to_left(Align::Min),
-                    |ui| ui.label("Time (seconds): "),
-                );
+                Some(())
+            });
+
+            if wave_changed {
+                cybergrind.sync_wave();
+            }
 
-                if column[1].text_edit_singleline(time).changed() {
-                    validate_f32(time);
+            ui.horizontal(|ui| {
+                ui.label("Legacy wave:");
+                if ui
+                    .add(TextEdit::singleline(&mut cybergrind.wave).desired_width(60.0))
+                    .changed()
+                {
+                    validate_u32(&mut cybergrind.wave);
                 }
+                ui.weak("Kept at the best wave on any difficulty for older versions");
+            });
 
-                column[0].add_space(7.0);
-                if column[0]
-                    .add_enabled(
-                        !classes.cybergrind.file_exists,
-                        Button::new("Create file"),
-                    )
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(!cybergrind.file_exists, Button::new("Create file"))
                     .clicked()
                 {
-                    classes.cybergrind.file_exists = true;
+                    cybergrind.file_exists = true;
                 }
 
-                column[1].add_space(4.0);
-                if column[1]
-                    .add_enabled(
-                        classes.cybergrind.file_exists,
-                        Button::new("Delete file"),
-                    )
+                if ui
+                    .add_enabled(cybergrind.file_exists, Button::new("Delete file"))
                     .clicked()
                 {
-                    classes.cybergrind.file_exists = false;
+                    cybergrind.file_exists = false;
                 }
             });
 
-            ui.set_width(available_width);
-
             Some(())
         });
     }
@@ -588,6 +2395,9 @@ impl App for SaveEditorApp {
     fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
         CentralPanel::default().show(ctx, |ui| {
             self.update_top_bar(ui);
+            self.update_archive_bar(ui);
+            self.update_preset_bar(ui);
+            self.update_slot_bar(ui);
             ui.separator();
 
             let available_height = ui.available_height();
@@ -604,39 +2414,336 @@ impl App for SaveEditorApp {
                 });
             });
         });
+
+        self.update_save_warnings(ctx);
+        self.update_repairs(ctx);
+        self.update_slot_confirmation(ctx);
+        self.update_import_confirmation(ctx);
+        self.update_preset_confirmation(ctx);
+        self.update_pattern_overwrite_confirmation(ctx);
+        self.update_slot_overview(ctx);
+        self.update_stats(ctx);
+        self.update_rank_grid(ctx);
+        self.update_primes(ctx);
+        self.update_bestiary(ctx);
+        self.update_patterns(ctx);
+        self.update_sandbox(ctx);
     }
 }
 
-fn detect_save_path(save_slot: &SaveSlot) -> Option<PathBuf> {
-    let regkey = Hive::LocalMachine
-        .open(
-            r"SOFTWARE\WOW6432Node\Valve\Steam",
-            Security::Read,
-        )
-        .ok()?;
+fn overview_row<F: Fn(&SlotSummary) -> String>(
+    ui: &mut Ui,
+    label: &str,
+    summaries: &[(Slot, SlotSummary)],
+    value: F,
+) {
+    ui.label(label);
+    for (_, summary) in summaries {
+        ui.label(value(summary));
+    }
+    ui.end_row();
+}
 
-    let data = regkey.value("InstallPath").ok()?;
+fn vector_edit(ui: &mut Ui, vector: &mut Vector) -> bool {
+    ui.horizontal(|ui| {
+        let mut changed = false;
+        for value in [&mut vector.x, &mut vector.y, &mut vector.z] {
+            changed |= ui.add(DragValue::new(value).speed(0.1)).changed();
+        }
+        changed
+    })
+    .inner
+}
 
-    match data {
-        Data::String(path_str) => {
-            let mut path = PathBuf::from(path_str.to_os_string());
-            path.push(format!(
-                r"steamapps\common\ULTRAKILL\Saves\Slot{}",
-                *save_slot as u8
-            ));
+/// Rotations are edited as Euler angles, like in Unity, and stored back as
+/// quaternions.
+fn sandbox_grid(ui: &mut Ui, category: SandboxCategory, objects: &mut [SandboxObject]) {
+    Grid::new(format!("sandbox {} grid", category))
+        .striped(true)
+        .show(ui, |ui| {
+            let scale = match category {
+                SandboxCategory::Blocks => "Size",
+                _ => "Scale",
+            };
+            for header in ["Object", "Position", "Rotation", scale] {
+                ui.strong(header);
+            }
+            ui.end_row();
+
+            for (i, object) in objects.iter_mut().enumerate() {
+                match category == SandboxCategory::Enemies {
+                    true => {
+                        let selected = match object.get_enemy_type() {
+                            Some(enemy) => enemy.to_string(),
+                            None => object.object_identifier.clone(),
+                        };
+                        ComboBox::from_id_source(format!("sandbox enemy {}", i))
+                            .selected_text(selected)
+                            .width(180.0)
+                            .show_ui(ui, |ui| {
+                                for enemy in EnemyType::iter() {
+                                    if enemy.get_sandbox_id().is_some()
+                                        && ui.selectable_label(false, enemy.to_string()).clicked()
+                                    {
+                                        object.set_enemy_type(enemy);
+                                    }
+                                }
+                            });
+                    }
+                    false => {
+                        ui.label(&object.object_identifier);
+                    }
+                }
+
+                vector_edit(ui, &mut object.position);
+
+                let mut angles = object.rotation.to_euler();
+                if vector_edit(ui, &mut angles) {
+                    object.rotation = Quaternion::from_euler(angles);
+                }
+
+                // Blocks are sized rather than scaled.
+                match &mut object.block_size {
+                    Some(size) => vector_edit(ui, size),
+                    None => vector_edit(ui, &mut object.scale),
+                };
+                ui.end_row();
+            }
+        });
+}
+
+/// Higher pillars are drawn lighter.
+fn height_color(height: i32) -> Color32 {
+    let height = (height.clamp(-10, 40) + 10) as f32 / 50.0;
+    Color32::from_gray((30.0 + height * 150.0) as u8)
+}
 
-            if path.try_exists().ok()? {
-                Some(path)
-            } else {
-                None
+fn prefab_color(prefab: &Prefab) -> Color32 {
+    match prefab {
+        Prefab::None => Color32::WHITE,
+        Prefab::MeleeSpawn => Color32::RED,
+        Prefab::ProjectileSpawn => Color32::GOLD,
+        Prefab::JumpPad => Color32::GREEN,
+        Prefab::Stairs => Color32::LIGHT_BLUE,
+        Prefab::HideousMass => Color32::from_rgb(200, 80, 255),
+    }
+}
+
+fn rank_color(rank: &LevelRank) -> Color32 {
+    match rank {
+        LevelRank::None => Color32::GRAY,
+        LevelRank::D => Color32::from_rgb(0, 148, 255),
+        LevelRank::C => Color32::from_rgb(76, 255, 0),
+        LevelRank::B => Color32::from_rgb(255, 216, 0),
+        LevelRank::A => Color32::from_rgb(255, 106, 0),
+        LevelRank::S => Color32::from_rgb(255, 0, 0),
+        LevelRank::P => Color32::from_rgb(255, 255, 255),
+    }
+}
+
+fn rank_text(rank: &LevelRank) -> RichText {
+    let text = match rank {
+        LevelRank::None => "-".to_string(),
+        rank => rank.to_string(),
+    };
+
+    RichText::new(text).strong().color(rank_color(rank))
+}
+
+fn rank_combo_box(ui: &mut Ui, id_source: impl Hash, rank: &mut LevelRank, width: f32) -> bool {
+    let previous = *rank;
+
+    ComboBox::from_id_source(id_source)
+        .selected_text(rank_text(rank))
+        .width(width)
+        .show_ui(ui, |ui| {
+            for value in LevelRank::iter() {
+                ui.selectable_value(rank, value, rank_text(&value));
+            }
+        });
+
+    *rank != previous
+}
+
+fn lockable_combo_box(ui: &mut Ui, id_source: impl Hash, state: &mut Lockable) -> bool {
+    let previous = *state;
+
+    ComboBox::from_id_source(id_source)
+        .selected_text(state.to_string())
+        .width(85.0)
+        .show_ui(ui, |ui| {
+            for value in Lockable::iter() {
+                ui.selectable_value(state, value, value.to_string());
+            }
+        });
+
+    *state != previous
+}
+
+fn bestiary_text(state: &Lockable) -> &'static str {
+    match state {
+        Lockable::Locked => "Undiscovered",
+        Lockable::Unlocked => "Partially Discovered",
+        Lockable::Completed => "Fully Discovered",
+    }
+}
+
+fn bestiary_combo_box(ui: &mut Ui, id_source: impl Hash, state: &mut Lockable) -> bool {
+    let previous = *state;
+
+    ComboBox::from_id_source(id_source)
+        .selected_text(bestiary_text(state))
+        .width(140.0)
+        .show_ui(ui, |ui| {
+            for value in Lockable::iter() {
+                ui.selectable_value(state, value, bestiary_text(&value));
+            }
+        });
+
+    *state != previous
+}
+
+fn bestiary_fill_buttons(ui: &mut Ui) -> Option<Lockable> {
+    let mut selected = None;
+
+    ui.horizontal(|ui| {
+        ui.label("Mark all:");
+        for state in Lockable::iter() {
+            if ui.button(bestiary_text(&state)).clicked() {
+                selected = Some(state);
+            }
+        }
+    });
+
+    selected
+}
+
+fn bestiary_grid(
+    ui: &mut Ui,
+    general: &GeneralData,
+    enemies: &[EnemyType],
+    edits: &mut Vec<(EnemyType, Lockable)>,
+) {
+    Grid::new(format!("bestiary {:?}", enemies)).show(ui, |ui| {
+        for enemy in enemies {
+            ui.label(enemy.to_string());
+
+            let mut state = general
+                .enemies_discovered
+                .get(enemy)
+                .copied()
+                .unwrap_or_default();
+            if bestiary_combo_box(ui, format!("enemy {} state", *enemy as u8), &mut state) {
+                edits.push((*enemy, state));
+            }
+            ui.end_row();
+        }
+    });
+}
+
+fn rank_fill_menu(ui: &mut Ui, label: &str) -> Option<LevelRank> {
+    let mut selected = None;
+
+    ui.menu_button(label, |ui| {
+        for rank in LevelRank::iter() {
+            if ui.button(rank_text(&rank)).clicked() {
+                selected = Some(rank);
+                ui.close_menu();
+            }
+        }
+    });
+
+    selected
+}
+
+fn bulk_menu(
+    ui: &mut Ui,
+    scope: Scope,
+    difficulty: Difficulty,
+    all_difficulties: &mut bool,
+) -> Option<BulkEdit> {
+    let mut action = None;
+
+    ui.label(scope.to_string());
+    ui.checkbox(all_difficulties, "All difficulties");
+    ui.separator();
+
+    ui.menu_button("Set rank", |ui| {
+        for rank in LevelRank::iter() {
+            if ui.button(rank_text(&rank)).clicked() {
+                action = Some(BulkAction::SetRank(rank));
             }
         }
-        _ => None,
+    });
+
+    for value in [
+        BulkAction::FindSecrets,
+        BulkAction::SetChallenge(true),
+        BulkAction::SetChallenge(false),
+        BulkAction::ClearMajorAssists,
+        BulkAction::CreateFiles,
+        BulkAction::DeleteFiles,
+    ] {
+        if ui.button(value.to_string()).clicked() {
+            action = Some(value);
+        }
     }
+
+    if !scope.get_layers().is_empty() {
+        ui.menu_button("Secret missions", |ui| {
+            for state in [Lockable::Locked, Lockable::Unlocked, Lockable::Completed] {
+                if ui.button(state.to_string()).clicked() {
+                    action = Some(BulkAction::SetSecretMissions(state));
+                }
+            }
+        });
+    }
+
+    if !scope.get_enemies().is_empty() {
+        ui.menu_button("Bestiary", |ui| {
+            for state in Lockable::iter() {
+                if ui.button(bestiary_text(&state)).clicked() {
+                    action = Some(BulkAction::SetBestiary(state));
+                }
+            }
+        });
+    }
+
+    let action = action?;
+    ui.close_menu();
+
+    Some(BulkEdit {
+        action,
+        scope,
+        difficulty: match *all_difficulties {
+            true => None,
+            false => Some(difficulty),
+        },
+    })
+}
+
+fn slot_combo_box(ui: &mut Ui, id_source: &str, slots: &[Slot], selected: &mut usize) {
+    ComboBox::from_id_source(id_source)
+        .selected_text(
+            slots
+                .get(*selected)
+                .map(|slot| slot.to_string())
+                .unwrap_or_default(),
+        )
+        .width(100.0)
+        .show_ui(ui, |ui| {
+            for (i, slot) in slots.iter().enumerate() {
+                ui.selectable_value(selected, i, slot.to_string());
+            }
+        });
 }
 
 fn validate_f32(value: &mut String) {
-    if !value.is_empty() && value.parse::<f32>().is_err() {
+    if !value.is_empty()
+        && !value
+            .parse::<f32>()
+            .is_ok_and(|value| value.is_finite() && value >= 0.0)
+    {
         let mut new_value = String::new();
         let mut encountered_decimal_point = false;
 
@@ -649,7 +2756,7 @@ fn validate_f32(value: &mut String) {
             }
         }
 
-        if !new_value.parse::<f32>().is_err() {
+        if new_value.parse::<f32>().is_ok_and(f32::is_finite) {
             *value = new_value;
         } else {
             *value = String::new();
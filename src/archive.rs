@@ -0,0 +1,156 @@
+use crate::{
+    class::{
+        classes::Classes,
+        storage::{is_save_file, Storage, ZipStorage},
+    },
+    enums::{Difficulty, LevelRank},
+    saves::{copy_slot, SlotSummary},
+};
+use ms_nrbf::Stream;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    io::{self, Cursor, Read, Seek, Write},
+};
+use strum::IntoEnumIterator;
+use thiserror::Error;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("invalid manifest: {0}")]
+    InvalidManifest(#[from] serde_json::Error),
+    #[error("archive has no manifest")]
+    MissingManifest,
+    #[error("archive is missing {0}")]
+    MissingFile(String),
+    #[error("{0} does not match its hash in the manifest")]
+    HashMismatch(String),
+    #[error("{0} is not listed in the manifest")]
+    UnlistedFile(String),
+    #[error("{0} is not a valid save file")]
+    InvalidFile(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub editor_version: String,
+    pub source_slot: String,
+    pub difficulties: Vec<String>,
+    pub files: BTreeMap<String, String>,
+}
+
+fn hash(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+fn get_difficulty_coverage(classes: &Classes) -> Vec<String> {
+    Difficulty::iter()
+        .filter(|difficulty| {
+            classes
+                .difficulty
+                .get(difficulty)
+                .is_some_and(|data| data.file_exists)
+                || classes.levels.values().any(|level| {
+                    level.file_exists
+                        && level
+                            .ranks
+                            .get(*difficulty as usize)
+                            .is_some_and(|rank| *rank != LevelRank::None)
+                })
+        })
+        .map(|difficulty| difficulty.to_string())
+        .collect()
+}
+
+pub fn export_slot<S: Storage, W: Write + Seek>(
+    storage: &S,
+    source_slot: &str,
+    writer: W,
+) -> Result<Manifest, ArchiveError> {
+    let mut archive = ZipStorage::new();
+    let mut files = BTreeMap::new();
+
+    for name in storage.save_files()? {
+        if let Some(data) = storage.read(&name)? {
+            files.insert(name.clone(), hash(&data));
+            archive.write(&name, &data)?;
+        }
+    }
+
+    let manifest = Manifest {
+        editor_version: env!("CARGO_PKG_VERSION").to_string(),
+        source_slot: source_slot.to_string(),
+        difficulties: Classes::load(storage)
+            .map(|classes| get_difficulty_coverage(&classes))
+            .unwrap_or_default(),
+        files,
+    };
+
+    archive.write(MANIFEST_FILE, &serde_json::to_vec_pretty(&manifest)?)?;
+    archive.write_to(writer)?;
+
+    Ok(manifest)
+}
+
+#[derive(Debug)]
+pub struct SlotArchive {
+    pub manifest: Manifest,
+    files: ZipStorage,
+}
+
+impl SlotArchive {
+    /// Reads an exported slot and checks every file against the manifest.
+    pub fn open<R: Read + Seek>(reader: R) -> Result<Self, ArchiveError> {
+        let files = ZipStorage::open(reader)?;
+        let manifest: Manifest = serde_json::from_slice(
+            &files
+                .read(MANIFEST_FILE)?
+                .ok_or(ArchiveError::MissingManifest)?,
+        )?;
+
+        for name in files.files()? {
+            if name != MANIFEST_FILE && !manifest.files.contains_key(&name) {
+                return Err(ArchiveError::UnlistedFile(name));
+            }
+        }
+
+        for (name, file_hash) in &manifest.files {
+            if !is_save_file(name) {
+                return Err(ArchiveError::InvalidFile(name.clone()));
+            }
+
+            let data = files
+                .read(name)?
+                .ok_or_else(|| ArchiveError::MissingFile(name.clone()))?;
+
+            if hash(&data) != *file_hash {
+                return Err(ArchiveError::HashMismatch(name.clone()));
+            }
+
+            if Stream::decode(&mut Cursor::new(data)).is_err() {
+                return Err(ArchiveError::InvalidFile(name.clone()));
+            }
+        }
+
+        Ok(Self { manifest, files })
+    }
+
+    pub fn load_classes(&self) -> Option<Classes> {
+        Classes::load(&self.files)
+    }
+
+    pub fn get_summary(&self) -> io::Result<SlotSummary> {
+        SlotSummary::load(&self.files)
+    }
+
+    /// Replaces every save file in `target` with the archived ones. If a write
+    /// fails, `target` is put back the way it was.
+    pub fn import_into<S: Storage>(&self, target: &mut S) -> Result<(), ArchiveError> {
+        Ok(copy_slot(&self.files, target)?)
+    }
+}
@@ -0,0 +1,338 @@
+use crate::{
+    class::{classes::Classes, level::LevelData},
+    enums::{Act, Difficulty, EnemyType, Layer, Level, LevelRank, Lockable},
+    primes::{set_level_rank, Prime},
+};
+use std::{fmt, str::FromStr};
+use strum::IntoEnumIterator;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ScopeParseError {
+    #[error("unknown scope {0}, expected all, act:<N>, layer:<N> or level:<N-N>")]
+    InvalidScope(String),
+    #[error("unknown {0} {1}")]
+    UnknownTarget(&'static str, String),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Scope {
+    All,
+    Act(Act),
+    Layer(Layer),
+    Level(Level),
+}
+
+impl Scope {
+    pub fn get_layers(&self) -> Vec<Layer> {
+        match self {
+            Self::All => Layer::iter().collect(),
+            Self::Act(act) => act.get_layers().to_vec(),
+            Self::Layer(layer) => vec![*layer],
+            Self::Level(_) => vec![],
+        }
+    }
+
+    pub fn get_levels(&self) -> Vec<Level> {
+        match self {
+            Self::All => Level::iter().collect(),
+            Self::Level(level) => vec![*level],
+            _ => self
+                .get_layers()
+                .iter()
+                .flat_map(|layer| layer.get_levels().iter().copied())
+                .collect(),
+        }
+    }
+
+    /// Enemies first encountered in the scope. Secret enemies are only part of
+    /// `All`.
+    pub fn get_enemies(&self) -> Vec<EnemyType> {
+        match self {
+            Self::All => EnemyType::iter().collect(),
+            _ => self
+                .get_levels()
+                .iter()
+                .flat_map(|level| level.get_new_enemies().iter().copied())
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => write!(f, "all levels"),
+            Self::Act(act) => write!(f, "{}", act),
+            Self::Layer(layer) => write!(f, "{}", layer),
+            Self::Level(level) => write!(f, "{}", level),
+        }
+    }
+}
+
+/// Finds a variant by the code before the `:` in its name, such as `1-2` for
+/// `1-2: THE BURNING WORLD` or `P-1` for `P-1: SOUL SURVIVOR`.
+fn find_by_code<T: IntoEnumIterator + fmt::Display>(code: &str) -> Option<T> {
+    T::iter().find(|value| {
+        value
+            .to_string()
+            .split(':')
+            .next()
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(code))
+    })
+}
+
+impl FromStr for Scope {
+    type Err = ScopeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("all") {
+            return Ok(Self::All);
+        }
+
+        let (kind, target) = s
+            .split_once(':')
+            .ok_or_else(|| ScopeParseError::InvalidScope(s.to_string()))?;
+        let unknown = |kind| ScopeParseError::UnknownTarget(kind, target.to_string());
+
+        match kind.to_ascii_lowercase().as_str() {
+            "act" => target
+                .parse()
+                .ok()
+                .and_then(Act::from_repr)
+                .map(Self::Act)
+                .ok_or_else(|| unknown("act")),
+            "layer" => target
+                .parse()
+                .ok()
+                .and_then(Layer::from_repr)
+                .map(Self::Layer)
+                .ok_or_else(|| unknown("layer")),
+            "level" => parse_level(target)
+                .map(Self::Level)
+                .ok_or_else(|| unknown("level")),
+            _ => Err(ScopeParseError::InvalidScope(s.to_string())),
+        }
+    }
+}
+
+/// Parses a level by its code, such as `4-2`, or by its number.
+pub fn parse_level(s: &str) -> Option<Level> {
+    find_by_code(s).or_else(|| s.parse().ok())
+}
+
+/// Parses a difficulty by its name or its number.
+pub fn parse_difficulty(s: &str) -> Option<Difficulty> {
+    Difficulty::iter()
+        .find(|difficulty| difficulty.to_string().eq_ignore_ascii_case(s))
+        .or_else(|| s.parse().ok())
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BulkAction {
+    SetRank(LevelRank),
+    FindSecrets,
+    SetChallenge(bool),
+    ClearMajorAssists,
+    CreateFiles,
+    DeleteFiles,
+    SetSecretMissions(Lockable),
+    SetBestiary(Lockable),
+}
+
+impl fmt::Display for BulkAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SetRank(LevelRank::None) => write!(f, "Clear ranks"),
+            Self::SetRank(rank) => write!(f, "Set rank {}", rank),
+            Self::FindSecrets => write!(f, "Find all secrets"),
+            Self::SetChallenge(true) => write!(f, "Complete challenges"),
+            Self::SetChallenge(false) => write!(f, "Reset challenges"),
+            Self::ClearMajorAssists => write!(f, "Clear major assists"),
+            Self::CreateFiles => write!(f, "Create files"),
+            Self::DeleteFiles => write!(f, "Delete files"),
+            Self::SetSecretMissions(state) => write!(f, "Set secret missions {}", state),
+            Self::SetBestiary(state) => write!(f, "Set bestiary entries {}", state),
+        }
+    }
+}
+
+/// One action applied to every level in a scope. Ranks and major assists
+/// are only changed for `difficulty`, or for every difficulty if it's `None`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BulkEdit {
+    pub action: BulkAction,
+    pub scope: Scope,
+    pub difficulty: Option<Difficulty>,
+}
+
+impl BulkEdit {
+    fn get_difficulties(&self) -> Vec<Difficulty> {
+        match self.difficulty {
+            Some(difficulty) => vec![difficulty],
+            None => Difficulty::iter().collect(),
+        }
+    }
+
+    /// Applies `edit` to every level in the scope, counting the levels it
+    /// changed.
+    fn edit_levels<F>(&self, classes: &mut Classes, mut edit: F) -> usize
+    where
+        F: FnMut(&mut LevelData) -> bool,
+    {
+        let mut changed = 0;
+
+        for level in self.scope.get_levels() {
+            if let Some(level_data) = classes.levels.get_mut(&level) {
+                changed += edit(level_data) as usize;
+            }
+        }
+
+        changed
+    }
+
+    /// Ranks go through `set_level_rank`, so prime sanctums stay consistent
+    /// with their state and secret mission.
+    fn set_ranks(&self, classes: &mut Classes, rank: LevelRank) -> usize {
+        let mut changed = 0;
+
+        for level in self.scope.get_levels() {
+            let Some(level_data) = classes.levels.get(&level) else {
+                continue;
+            };
+            let before = (level_data.ranks.clone(), level_data.file_exists);
+
+            for difficulty in self.get_difficulties() {
+                set_level_rank(classes, level, difficulty, rank);
+            }
+
+            let level_data = &classes.levels[&level];
+            changed += (before != (level_data.ranks.clone(), level_data.file_exists)) as usize;
+        }
+
+        changed
+    }
+
+    /// Secret missions belong to layers, so a single level has none. Prime
+    /// secret missions go through `Prime`, so their states follow, and
+    /// placeholders for missions the game doesn't have yet are skipped.
+    fn set_secret_missions(&self, classes: &mut Classes, state: Lockable) -> usize {
+        let mut changed = 0;
+
+        for layer in self.scope.get_layers() {
+            let secret_level = layer.get_secret_level();
+            if secret_level.is_placeholder() {
+                continue;
+            }
+
+            let Some(prime) = Prime::from_secret_level(&secret_level) else {
+                if classes.general.secret_missions.insert(secret_level, state) != Some(state) {
+                    classes.general.file_exists = true;
+                    changed += 1;
+                }
+                continue;
+            };
+
+            let get_progress = |classes: &Classes| {
+                let states: Vec<_> = Difficulty::iter()
+                    .map(|difficulty| prime.get_state(classes, difficulty))
+                    .collect();
+                (prime.get_secret_mission(classes), states)
+            };
+            let before = get_progress(classes);
+
+            match self.difficulty {
+                Some(difficulty) => prime.set_secret_mission(classes, difficulty, state),
+                None => {
+                    for difficulty in Difficulty::iter() {
+                        prime.set_state(classes, difficulty, state);
+                    }
+                }
+            }
+
+            changed += (get_progress(classes) != before) as usize;
+        }
+
+        changed
+    }
+
+    fn set_bestiary(&self, classes: &mut Classes, state: Lockable) -> usize {
+        let general = &mut classes.general;
+        let mut changed = 0;
+
+        for enemy in self.scope.get_enemies() {
+            if general.enemies_discovered.insert(enemy, state) != Some(state) {
+                changed += 1;
+            }
+        }
+
+        if self.scope == Scope::All {
+            for value in general.unknown_enemies.values_mut() {
+                if *value != state {
+                    *value = state;
+                    changed += 1;
+                }
+            }
+        }
+
+        general.file_exists |= changed > 0;
+        changed
+    }
+
+    /// Applies the edit and returns how many levels, secret missions or
+    /// enemies it changed.
+    pub fn apply(&self, classes: &mut Classes) -> usize {
+        let difficulties = self.get_difficulties();
+
+        match self.action {
+            BulkAction::SetRank(rank) => self.set_ranks(classes, rank),
+            BulkAction::FindSecrets => self.edit_levels(classes, |level_data| {
+                let changed = level_data.secrets_found.contains(&false) || !level_data.file_exists;
+                level_data.secrets_found.fill(true);
+                level_data.file_exists = true;
+                changed
+            }),
+            BulkAction::SetChallenge(challenge) => self.edit_levels(classes, |level_data| {
+                let changed =
+                    level_data.challenge != challenge || (challenge && !level_data.file_exists);
+                level_data.challenge = challenge;
+                level_data.file_exists |= challenge;
+                changed
+            }),
+            BulkAction::ClearMajorAssists => self.edit_levels(classes, |level_data| {
+                let mut changed = false;
+                for difficulty in &difficulties {
+                    if let Some(value) = level_data.major_assists.get_mut(*difficulty as usize) {
+                        changed |= *value;
+                        *value = false;
+                    }
+                }
+                changed
+            }),
+            BulkAction::CreateFiles => self.edit_levels(classes, |level_data| {
+                !std::mem::replace(&mut level_data.file_exists, true)
+            }),
+            BulkAction::DeleteFiles => self.edit_levels(classes, |level_data| {
+                std::mem::replace(&mut level_data.file_exists, false)
+            }),
+            BulkAction::SetSecretMissions(state) => self.set_secret_missions(classes, state),
+            BulkAction::SetBestiary(state) => self.set_bestiary(classes, state),
+        }
+    }
+}
+
+impl fmt::Display for BulkEdit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} for {}", self.action, self.scope)?;
+
+        match (self.action, self.difficulty) {
+            (BulkAction::SetRank(_) | BulkAction::ClearMajorAssists, Some(difficulty)) => {
+                write!(f, " on {}", difficulty)
+            }
+            (BulkAction::SetRank(_) | BulkAction::ClearMajorAssists, None) => {
+                write!(f, " on every difficulty")
+            }
+            _ => Ok(()),
+        }
+    }
+}
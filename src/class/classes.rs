@@ -1,14 +1,19 @@
 use super::{
-    cybergrind::CybergrindData, difficulty::DifficultyData, general::GeneralData, level::LevelData,
+    cybergrind::CybergrindData,
+    difficulty::DifficultyData,
+    general::GeneralData,
+    level::LevelData,
+    repair::FileRepair,
+    storage::{MemoryStorage, Storage},
     traits::LoadableSavable,
 };
 use crate::enums::{Difficulty, Level};
-use std::{collections::BTreeMap, io, path::Path};
+use std::{collections::BTreeMap, io};
 
 type LevelMap = BTreeMap<Level, LevelData>;
 type DifficultyMap = BTreeMap<Difficulty, DifficultyData>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Classes {
     pub levels: LevelMap,
     pub cybergrind: CybergrindData,
@@ -16,20 +21,47 @@ pub struct Classes {
     pub general: GeneralData,
 }
 
+impl Default for Classes {
+    fn default() -> Self {
+        let storage = MemoryStorage::new();
+
+        Self {
+            levels: LevelMap::load(&storage),
+            cybergrind: CybergrindData::load(&storage),
+            difficulty: DifficultyMap::load(&storage),
+            general: GeneralData::load(&storage),
+        }
+    }
+}
+
 impl Classes {
-    pub fn load<P: AsRef<Path>>(save_path: P) -> Option<Self> {
+    pub fn load<S: Storage>(storage: &S) -> Option<Self> {
+        let mut cybergrind = CybergrindData::load(storage);
+        cybergrind.migrate();
+
         Some(Self {
-            levels: LevelMap::load(&save_path),
-            cybergrind: CybergrindData::load(&save_path),
-            difficulty: DifficultyMap::load(&save_path),
-            general: GeneralData::load(&save_path),
+            levels: LevelMap::load(storage),
+            cybergrind,
+            difficulty: DifficultyMap::load(storage),
+            general: GeneralData::load(storage),
         })
     }
 
-    pub fn save<P: AsRef<Path>>(&self, save_path: P) -> Result<(), io::Error> {
-        self.levels.save(&save_path)?;
-        self.cybergrind.save(&save_path)?;
-        self.difficulty.save(&save_path)?;
-        self.general.save(&save_path)
+    pub fn save<S: Storage>(&self, storage: &mut S) -> Result<(), io::Error> {
+        self.levels.save(storage)?;
+        self.cybergrind.save(storage)?;
+        self.difficulty.save(storage)?;
+        self.general.save(storage)
+    }
+
+    /// Rewrites every save file that is malformed or has arrays of the wrong
+    /// length, returning what was changed in each.
+    pub fn repair<S: Storage>(storage: &mut S) -> Result<Vec<FileRepair>, io::Error> {
+        let mut repairs = LevelMap::repair(storage)?;
+        repairs.extend(CybergrindData::repair(storage)?);
+        repairs.extend(DifficultyMap::repair(storage)?);
+        repairs.extend(GeneralData::repair(storage)?);
+
+        Ok(repairs)
     }
 }
@@ -0,0 +1,212 @@
+use super::{
+    macros::{impl_int32_element, impl_primitive},
+    traits::{ArrayIndex, FieldMap, FieldName},
+};
+use crate::enums::{LevelRank, Lockable};
+use ms_nrbf::{Class, Field, Primitive, PrimitiveArray};
+use std::{collections::BTreeMap, marker::PhantomData, str::FromStr};
+use strum::IntoEnumIterator;
+
+pub(super) trait Codec<T> {
+    fn read(field: Option<&Field>) -> Option<T>;
+    fn write(value: &T) -> Option<Field>;
+}
+
+pub(super) trait PrimitiveValue
+where
+    Self: Sized + Copy + Default,
+{
+    fn read_primitive(field: Option<&Field>) -> Self;
+    fn write_primitive(&self) -> Field;
+}
+
+pub(super) trait ArrayElement
+where
+    Self: Sized + Copy + Default,
+{
+    fn read_array(field: Option<&Field>) -> Option<Vec<Self>>;
+    fn write_array(values: &[Self]) -> Field;
+}
+
+impl_primitive!(bool, Boolean);
+impl_primitive!(i32, Int32);
+impl_primitive!(f32, Single);
+
+impl_int32_element!(Lockable, |value: i32| Lockable::from_repr(value as u8));
+impl_int32_element!(LevelRank, |value: i32| Some(LevelRank::from(value)));
+
+pub(super) struct Plain;
+
+impl<T: PrimitiveValue> Codec<T> for Plain {
+    fn read(field: Option<&Field>) -> Option<T> {
+        Some(T::read_primitive(field))
+    }
+
+    fn write(value: &T) -> Option<Field> {
+        Some(value.write_primitive())
+    }
+}
+
+impl<T: ArrayElement> Codec<Vec<T>> for Plain {
+    fn read(field: Option<&Field>) -> Option<Vec<T>> {
+        T::read_array(field)
+    }
+
+    fn write(value: &Vec<T>) -> Option<Field> {
+        Some(T::write_array(value))
+    }
+}
+
+pub(super) struct IntBool;
+
+impl Codec<bool> for IntBool {
+    fn read(field: Option<&Field>) -> Option<bool> {
+        Some(i32::read_primitive(field) != 0)
+    }
+
+    fn write(value: &bool) -> Option<Field> {
+        Some((*value as i32).write_primitive())
+    }
+}
+
+pub(super) struct Text<T>(PhantomData<T>);
+
+fn parse_text<T: FromStr + Default>(value: &str) -> Option<T> {
+    match value.is_empty() {
+        true => Some(T::default()),
+        false => value.parse().ok(),
+    }
+}
+
+impl<T: PrimitiveValue + ToString + FromStr> Codec<String> for Text<T> {
+    fn read(field: Option<&Field>) -> Option<String> {
+        Some(T::read_primitive(field).to_string())
+    }
+
+    fn write(value: &String) -> Option<Field> {
+        Some(parse_text::<T>(value)?.write_primitive())
+    }
+}
+
+impl<T: ArrayElement + ToString + FromStr> Codec<Vec<String>> for Text<T> {
+    fn read(field: Option<&Field>) -> Option<Vec<String>> {
+        Some(
+            T::read_array(field)?
+                .iter()
+                .map(|value| value.to_string())
+                .collect(),
+        )
+    }
+
+    fn write(value: &Vec<String>) -> Option<Field> {
+        let mut values = vec![];
+
+        for value in value {
+            values.push(parse_text::<T>(value)?);
+        }
+
+        Some(T::write_array(&values))
+    }
+}
+
+pub(super) fn read_field_map<C, K, V>(class: &Class) -> Option<BTreeMap<K, V>>
+where
+    C: Codec<V>,
+    K: FieldName + IntoEnumIterator + Ord,
+{
+    let mut map = BTreeMap::new();
+
+    for key in K::iter() {
+        let value = C::read(class.fields.get(key.field_name()))?;
+        map.insert(key, value);
+    }
+
+    Some(map)
+}
+
+pub(super) fn write_field_map<C, K, V>(fields: &mut FieldMap, map: &BTreeMap<K, V>) -> Option<()>
+where
+    C: Codec<V>,
+    K: FieldName + Ord,
+{
+    for (key, value) in map {
+        fields.insert(key.field_name().to_string(), C::write(value)?);
+    }
+
+    Some(())
+}
+
+/// Reads the values at indexes that `K` has a variant for. The others are
+/// kept by `read_unknown_indexes`.
+pub(super) fn read_indexed_map<K, V>(field: Option<&Field>) -> Option<BTreeMap<K, V>>
+where
+    K: ArrayIndex + Ord,
+    V: ArrayElement,
+{
+    let mut map = BTreeMap::new();
+
+    for (i, value) in V::read_array(field)?.into_iter().enumerate() {
+        if let Some(key) = K::from_index(i) {
+            map.insert(key, value);
+        }
+    }
+
+    Some(map)
+}
+
+pub(super) fn write_indexed_map<K, V>(map: &BTreeMap<K, V>) -> Field
+where
+    K: ArrayIndex + Ord,
+    V: ArrayElement,
+{
+    let mut values = vec![];
+
+    for (key, value) in map {
+        if values.len() <= key.index() {
+            values.resize(key.index() + 1, V::default());
+        }
+
+        values[key.index()] = *value;
+    }
+
+    V::write_array(&values)
+}
+
+/// Reads the values at indexes that `K` has no variant for, so they can be
+/// written back instead of dropped.
+pub(super) fn read_unknown_indexes<K, V>(field: Option<&Field>) -> Option<BTreeMap<usize, V>>
+where
+    K: ArrayIndex,
+    V: ArrayElement,
+{
+    Some(
+        V::read_array(field)?
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| K::from_index(*i).is_none())
+            .collect(),
+    )
+}
+
+/// Sets the values at unknown indexes in an array already written to `fields`.
+pub(super) fn write_unknown_indexes<V>(
+    fields: &mut FieldMap,
+    name: &str,
+    unknown: &BTreeMap<usize, V>,
+) -> Option<()>
+where
+    V: ArrayElement,
+{
+    let mut values = V::read_array(fields.get(name))?;
+
+    for (i, value) in unknown {
+        if values.len() <= *i {
+            values.resize(i + 1, V::default());
+        }
+
+        values[*i] = *value;
+    }
+
+    fields.insert(name.to_string(), V::write_array(&values));
+    Some(())
+}
@@ -1,22 +1,28 @@
-use super::{
-    macros::{read_primitive_array, write_primitive, write_primitive_array},
-    traits::{FieldMap, ParsableClass},
-};
-use indexmap::IndexMap;
-use ms_nrbf::{Class, Field, Primitive, PrimitiveArray};
+use crate::enums::Difficulty;
+use bepis_derive::BepisClass;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, BepisClass)]
+#[bepis(class = "CyberRankData", file = "cybergrindhighscore.bepis")]
 pub struct CybergrindData {
-    pub waves: Vec<String>, //f32
-    pub kills: Vec<String>, //i32
-    pub style: Vec<String>, //i32
-    pub times: Vec<String>, //f32
+    /// The best wave from before high scores were kept per difficulty.
+    #[bepis(field = "wave", text = i32)]
+    pub wave: String,
+    #[bepis(field = "preciseWavesByDifficulty", text = f32)]
+    pub waves: Vec<String>,
+    #[bepis(field = "kills", text = i32)]
+    pub kills: Vec<String>,
+    #[bepis(field = "style", text = i32)]
+    pub style: Vec<String>,
+    #[bepis(field = "time", text = f32)]
+    pub times: Vec<String>,
+    #[bepis(file_exists)]
     pub file_exists: bool,
 }
 
 impl Default for CybergrindData {
     fn default() -> Self {
         Self {
+            wave: "0".to_string(),
             waves: vec!["0.0".to_string(); 6],
             kills: vec!["0".to_string(); 6],
             style: vec!["0".to_string(); 6],
@@ -26,86 +32,76 @@ impl Default for CybergrindData {
     }
 }
 
-macro_rules! parse_string_array {
-    ($array:expr) => {{
-        let mut new_array = vec![];
+impl CybergrindData {
+    /// Raises the legacy wave to the best wave reached on any difficulty. A
+    /// higher legacy wave is kept, since older versions of the game read it.
+    pub fn sync_wave(&mut self) {
+        let best = self
+            .waves
+            .iter()
+            .map(|wave| split_wave(wave.parse().unwrap_or_default()).0)
+            .max()
+            .unwrap_or_default();
+        let current = self.wave.parse().unwrap_or_default();
 
-        for value in $array {
-            new_array.push(match value.is_empty() {
-                true => <_>::default(),
-                false => value.parse().ok()?,
-            })
-        }
-
-        new_array
-    }};
-}
+        self.wave = best.max(current).to_string();
+    }
 
-const WAVE_INT_FIELD: &str = "wave";
-const WAVES_FIELD: &str = "preciseWavesByDifficulty";
-const KILLS_FIELD: &str = "kills";
-const STYLE_FIELD: &str = "style";
-const TIMES_FIELD: &str = "time";
+    /// Brings a save from before the per difficulty arrays up to date. Missing
+    /// entries are added, and a legacy wave with no precise waves is moved to
+    /// the default difficulty.
+    pub fn migrate(&mut self) {
+        let default = Self::default();
+        for (values, defaults) in [
+            (&mut self.waves, default.waves),
+            (&mut self.kills, default.kills),
+            (&mut self.style, default.style),
+            (&mut self.times, default.times),
+        ] {
+            let len = values.len();
+            values.extend(defaults.into_iter().skip(len));
+        }
 
-impl ParsableClass for CybergrindData {
-    const CLASS_NAME: &'static str = "CyberRankData";
-    const FILE_NAME: &'static str = "cybergrindhighscore.bepis";
+        let wave: i32 = self.wave.parse().unwrap_or_default();
+        let no_waves = self
+            .waves
+            .iter()
+            .all(|wave| wave.parse::<f32>().unwrap_or_default() == 0.0);
 
-    fn get_file_exists(&self) -> bool {
-        self.file_exists
+        if wave > 0 && no_waves {
+            if let Some(value) = self.waves.get_mut(Difficulty::default() as usize) {
+                *value = wave.to_string();
+            }
+        }
     }
+}
 
-    fn parse(class: &Class) -> Option<Self> {
-        Some(Self {
-            waves: read_primitive_array!(class, WAVES_FIELD, Single)
-                .iter()
-                .map(|value| value.to_string())
-                .collect(),
-            kills: read_primitive_array!(class, KILLS_FIELD, Int32)
-                .iter()
-                .map(|value| value.to_string())
-                .collect(),
-            style: read_primitive_array!(class, STYLE_FIELD, Int32)
-                .iter()
-                .map(|value| value.to_string())
-                .collect(),
-            times: read_primitive_array!(class, TIMES_FIELD, Single)
-                .iter()
-                .map(|value| value.to_string())
-                .collect(),
-            file_exists: true,
-        })
-    }
+/// Formats a time in seconds as `mm:ss.fff`.
+pub fn format_time(seconds: f32) -> String {
+    let millis = (seconds.max(0.0) as f64 * 1000.0).round() as u64;
+
+    format!(
+        "{:02}:{:02}.{:03}",
+        millis / 60_000,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
 
-    fn unparse(&self) -> Option<FieldMap> {
-        let mut fields = IndexMap::new();
+/// Parses a time written as `mm:ss.fff`, or as plain seconds.
+pub fn parse_time(text: &str) -> Option<f32> {
+    let (minutes, seconds) = match text.split_once(':') {
+        Some((minutes, seconds)) => (minutes.trim().parse::<u32>().ok()?, seconds.trim()),
+        None => (0, text.trim()),
+    };
+    let seconds: f32 = seconds.parse().ok()?;
+    let time = minutes as f32 * 60.0 + seconds;
 
-        write_primitive!(fields, WAVE_INT_FIELD, Int32, 0);
-        write_primitive_array!(
-            fields,
-            WAVES_FIELD,
-            Single,
-            parse_string_array!(&self.waves)
-        );
-        write_primitive_array!(
-            fields,
-            KILLS_FIELD,
-            Int32,
-            parse_string_array!(&self.kills)
-        );
-        write_primitive_array!(
-            fields,
-            STYLE_FIELD,
-            Int32,
-            parse_string_array!(&self.style)
-        );
-        write_primitive_array!(
-            fields,
-            TIMES_FIELD,
-            Single,
-            parse_string_array!(&self.times)
-        );
+    (seconds >= 0.0 && time.is_finite()).then_some(time)
+}
 
-        Some(fields)
-    }
+/// Splits a precise wave, such as 12.5, into the wave reached and how far
+/// through the next one the run got, as a percentage.
+pub fn split_wave(wave: f32) -> (u32, f32) {
+    (wave.max(0.0) as u32, wave.max(0.0).fract() * 100.0)
 }
@@ -1,48 +1,60 @@
-macro_rules! read_primitive {
-    ($class:expr, $field_name:expr, $primitive_type:ident) => {
-        if let Some(Field::Primitive(Primitive::$primitive_type(value))) =
-            $class.fields.get($field_name)
-        {
-            *value
-        } else {
-            <_>::default()
-        }
-    };
-}
+macro_rules! impl_primitive {
+    ($value_type:ty, $primitive_type:ident) => {
+        impl PrimitiveValue for $value_type {
+            fn read_primitive(field: Option<&Field>) -> Self {
+                if let Some(Field::Primitive(Primitive::$primitive_type(value))) = field {
+                    *value
+                } else {
+                    <_>::default()
+                }
+            }
 
-macro_rules! read_primitive_array {
-    ($class:expr, $field_name:expr, $primitive_type:ident) => {{
-        if let Some(Field::PrimitiveArray(PrimitiveArray::$primitive_type(array))) =
-            $class.fields.get($field_name)
-        {
-            array.clone()
-        } else {
-            vec![]
+            fn write_primitive(&self) -> Field {
+                Field::Primitive(Primitive::$primitive_type(*self))
+            }
         }
-    }};
-}
 
-macro_rules! write_primitive {
-    ($fields:expr, $field_name:expr, $primitive_type:ident, $value:expr) => {
-        $fields.insert(
-            $field_name.to_string(),
-            Field::Primitive(Primitive::$primitive_type($value)),
-        )
+        impl ArrayElement for $value_type {
+            fn read_array(field: Option<&Field>) -> Option<Vec<Self>> {
+                if let Some(Field::PrimitiveArray(PrimitiveArray::$primitive_type(array))) = field
+                {
+                    Some(array.clone())
+                } else {
+                    Some(vec![])
+                }
+            }
+
+            fn write_array(values: &[Self]) -> Field {
+                Field::PrimitiveArray(PrimitiveArray::$primitive_type(values.to_vec()))
+            }
+        }
     };
 }
 
-macro_rules! write_primitive_array {
-    ($fields:expr, $field_name:expr, $primitive_type:ident, $value:expr) => {
-        $fields.insert(
-            $field_name.to_string(),
-            Field::PrimitiveArray(PrimitiveArray::$primitive_type(
-                $value.clone(),
-            )),
-        )
+macro_rules! impl_int32_element {
+    ($value_type:ty, $from_int32:expr) => {
+        impl ArrayElement for $value_type {
+            fn read_array(field: Option<&Field>) -> Option<Vec<Self>> {
+                let mut values = vec![];
+
+                for value in i32::read_array(field)? {
+                    values.push($from_int32(value)?);
+                }
+
+                Some(values)
+            }
+
+            fn write_array(values: &[Self]) -> Field {
+                i32::write_array(
+                    &values
+                        .iter()
+                        .map(|value| *value as i32)
+                        .collect::<Vec<i32>>(),
+                )
+            }
+        }
     };
 }
 
-pub(super) use read_primitive;
-pub(super) use read_primitive_array;
-pub(super) use write_primitive;
-pub(super) use write_primitive_array;
+pub(super) use impl_int32_element;
+pub(super) use impl_primitive;
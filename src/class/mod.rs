@@ -1,7 +1,12 @@
 pub mod classes;
+mod codec;
 pub mod cybergrind;
 pub mod difficulty;
 pub mod general;
 pub mod level;
 mod macros;
+pub mod repair;
+pub mod storage;
+#[cfg(test)]
+mod tests;
 pub mod traits;
@@ -0,0 +1,222 @@
+use super::traits::FieldMap;
+use ms_nrbf::{Class, Field, PrimitiveArray};
+use std::{fmt, mem::discriminant};
+
+pub const BACKUP_EXTENSION: &str = ".bak";
+
+/// What was changed in one save file, which is kept as `backup` before being
+/// overwritten. Files the editor can't rebuild have no backup and are left
+/// as they are.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileRepair {
+    pub file: String,
+    pub backup: Option<String>,
+    pub changes: Vec<String>,
+}
+
+impl FileRepair {
+    pub fn is_repaired(&self) -> bool {
+        self.backup.is_some()
+    }
+}
+
+impl fmt::Display for FileRepair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.backup {
+            Some(backup) => write!(f, "{} (original kept as {})", self.file, backup),
+            None => write!(f, "{} (could not be repaired, left unchanged)", self.file),
+        }
+    }
+}
+
+fn pad<T>(mut values: Vec<T>, defaults: Vec<T>) -> Vec<T> {
+    let len = values.len();
+    values.extend(defaults.into_iter().skip(len));
+
+    values
+}
+
+/// Pads a short array to the length of its default, keeping the values it
+/// has. Longer arrays are kept whole, since extra entries can come from a
+/// newer game version. Returns the default if the types don't match.
+fn pad_array(
+    array: PrimitiveArray,
+    default: PrimitiveArray,
+) -> Result<PrimitiveArray, PrimitiveArray> {
+    match (array, default) {
+        (PrimitiveArray::Boolean(values), PrimitiveArray::Boolean(defaults)) => {
+            Ok(PrimitiveArray::Boolean(pad(values, defaults)))
+        }
+        (PrimitiveArray::Int32(values), PrimitiveArray::Int32(defaults)) => {
+            Ok(PrimitiveArray::Int32(pad(values, defaults)))
+        }
+        (PrimitiveArray::Single(values), PrimitiveArray::Single(defaults)) => {
+            Ok(PrimitiveArray::Single(pad(values, defaults)))
+        }
+        (array, default) if discriminant(&array) == discriminant(&default) => Ok(array),
+        (_, default) => Err(default),
+    }
+}
+
+fn array_len(array: &PrimitiveArray) -> Option<usize> {
+    match array {
+        PrimitiveArray::Boolean(values) => Some(values.len()),
+        PrimitiveArray::Int32(values) => Some(values.len()),
+        PrimitiveArray::Single(values) => Some(values.len()),
+        _ => None,
+    }
+}
+
+fn same_type(field: &Field, default: &Field) -> bool {
+    match (field, default) {
+        (Field::Primitive(value), Field::Primitive(default)) => {
+            discriminant(value) == discriminant(default)
+        }
+        _ => discriminant(field) == discriminant(default),
+    }
+}
+
+/// Fixes one decoded field against its default, returning the field to use
+/// and what was changed.
+fn repair_field(name: &str, field: Field, default: Field) -> (Field, Option<String>) {
+    let wrong_type = || Some(format!("{}: wrong type, set to default", name));
+
+    match (field, default) {
+        (Field::PrimitiveArray(array), Field::PrimitiveArray(default)) => {
+            let (len, expected) = (array_len(&array), array_len(&default));
+
+            match pad_array(array, default) {
+                Ok(array) if len >= expected => (Field::PrimitiveArray(array), None),
+                Ok(array) => (
+                    Field::PrimitiveArray(array),
+                    Some(format!(
+                        "{}: padded from {} to {} entries",
+                        name,
+                        len.unwrap_or_default(),
+                        expected.unwrap_or_default()
+                    )),
+                ),
+                Err(default) => (Field::PrimitiveArray(default), wrong_type()),
+            }
+        }
+        (field, default) if same_type(&field, &default) => (field, None),
+        (_, default) => (default, wrong_type()),
+    }
+}
+
+fn get_int32_array<'a>(class: &'a mut Class, name: &str) -> Option<&'a mut Vec<i32>> {
+    match class.fields.get_mut(name) {
+        Some(Field::PrimitiveArray(PrimitiveArray::Int32(values))) => Some(values),
+        _ => None,
+    }
+}
+
+/// Resets the elements of an `Int32` array that `parse` rejects, such as an
+/// unknown rank, returning their indexes. Returns `None` if the field isn't
+/// such an array or is still rejected.
+fn reset_invalid_elements<P>(
+    class: &mut Class,
+    name: &str,
+    defaults: Vec<i32>,
+    parse: &P,
+) -> Option<Vec<usize>>
+where
+    P: Fn(&Class) -> Option<FieldMap>,
+{
+    let values = get_int32_array(class, name)?;
+    if values.len() < defaults.len() {
+        return None;
+    }
+
+    // Extra entries start out like the last default entry.
+    let mut baseline = defaults;
+    let extra = baseline.last().copied().unwrap_or_default();
+    baseline.resize(values.len(), extra);
+
+    let values = std::mem::replace(values, baseline);
+    parse(class)?;
+
+    let mut invalid = vec![];
+    for (i, value) in values.into_iter().enumerate() {
+        let default = std::mem::replace(&mut get_int32_array(class, name)?[i], value);
+
+        if parse(class).is_none() {
+            get_int32_array(class, name)?[i] = default;
+            invalid.push(i);
+        }
+    }
+
+    Some(invalid)
+}
+
+/// Rebuilds a class from the fields of `decoded` that can be read, starting
+/// from the fields of a new class and swapping in each decoded field that
+/// `parse` still accepts. Returns the fields to write and what was changed, or
+/// `None` if even the rebuilt class isn't accepted.
+pub(super) fn repair_fields<F, P>(
+    decoded: Option<Class>,
+    template: F,
+    parse: P,
+) -> Option<(FieldMap, Vec<String>)>
+where
+    F: Fn() -> Option<FieldMap>,
+    P: Fn(&Class) -> Option<FieldMap>,
+{
+    let Some(decoded) = decoded else {
+        return Some((
+            template()?,
+            vec!["could not be decoded, every field set to default".to_string()],
+        ));
+    };
+
+    let mut class = Class {
+        library_name: decoded.library_name,
+        name: decoded.name,
+        fields: template()?,
+    };
+    let mut changes = vec![];
+
+    for name in class.fields.keys() {
+        if !decoded.fields.contains_key(name) {
+            changes.push(format!("{}: missing, set to default", name));
+        }
+    }
+
+    for (name, field) in decoded.fields {
+        let field = match template()?.shift_remove(&name) {
+            Some(default) => {
+                let (field, change) = repair_field(&name, field, default);
+                changes.extend(change);
+                field
+            }
+            None => field,
+        };
+
+        let default = class.fields.insert(name.clone(), field);
+        if parse(&class).is_some() {
+            continue;
+        }
+
+        if let Some(Field::PrimitiveArray(PrimitiveArray::Int32(defaults))) = default {
+            if let Some(invalid) = reset_invalid_elements(&mut class, &name, defaults, &parse) {
+                changes.push(format!(
+                    "{}: invalid values at {:?} set to default",
+                    name, invalid
+                ));
+                continue;
+            }
+
+            class
+                .fields
+                .insert(name.clone(), template()?.shift_remove(&name)?);
+        } else {
+            match default {
+                Some(default) => class.fields.insert(name.clone(), default),
+                None => class.fields.shift_remove(&name),
+            };
+        }
+        changes.push(format!("{}: invalid value, set to default", name));
+    }
+
+    Some((parse(&class)?, changes))
+}
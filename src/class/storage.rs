@@ -0,0 +1,193 @@
+use std::{
+    collections::BTreeMap,
+    fs::{read, read_dir, remove_file, write},
+    io::{self, Read, Seek, Write},
+    path::{Path, PathBuf},
+};
+use zip::{write::FileOptions, ZipArchive, ZipWriter};
+
+pub const SAVE_FILE_EXTENSION: &str = ".bepis";
+
+pub fn is_save_file(name: &str) -> bool {
+    name.ends_with(SAVE_FILE_EXTENSION) && !name.contains(['/', '\\'])
+}
+
+pub trait Storage {
+    fn read(&self, name: &str) -> io::Result<Option<Vec<u8>>>;
+    fn write(&mut self, name: &str, data: &[u8]) -> io::Result<()>;
+    fn remove(&mut self, name: &str) -> io::Result<()>;
+    fn files(&self) -> io::Result<Vec<String>>;
+
+    fn save_files(&self) -> io::Result<Vec<String>> {
+        Ok(self
+            .files()?
+            .into_iter()
+            .filter(|name| is_save_file(name))
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DirectoryStorage {
+    path: PathBuf,
+}
+
+impl DirectoryStorage {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Storage for DirectoryStorage {
+    fn read(&self, name: &str) -> io::Result<Option<Vec<u8>>> {
+        match read(self.path.join(name)) {
+            Ok(data) => Ok(Some(data)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn write(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        write(self.path.join(name), data)
+    }
+
+    fn remove(&mut self, name: &str) -> io::Result<()> {
+        let path = self.path.join(name);
+
+        if path.exists() {
+            remove_file(path)?;
+        }
+
+        Ok(())
+    }
+
+    fn files(&self) -> io::Result<Vec<String>> {
+        let mut files = vec![];
+
+        if !self.path.exists() {
+            return Ok(files);
+        }
+
+        for entry in read_dir(&self.path)? {
+            let entry = entry?;
+
+            if entry.file_type()?.is_file() {
+                files.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+
+        files.sort();
+        Ok(files)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage {
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn copy_from<S: Storage>(storage: &S) -> io::Result<Self> {
+        let mut files = BTreeMap::new();
+
+        for name in storage.files()? {
+            if let Some(data) = storage.read(&name)? {
+                files.insert(name, data);
+            }
+        }
+
+        Ok(Self { files })
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn read(&self, name: &str) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.files.get(name).cloned())
+    }
+
+    fn write(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        self.files.insert(name.to_string(), data.to_vec());
+        Ok(())
+    }
+
+    fn remove(&mut self, name: &str) -> io::Result<()> {
+        self.files.remove(name);
+        Ok(())
+    }
+
+    fn files(&self) -> io::Result<Vec<String>> {
+        Ok(self.files.keys().cloned().collect())
+    }
+}
+
+/// A zip archive held in memory, read with [`ZipStorage::open`] and written
+/// back out with [`ZipStorage::write_to`].
+#[derive(Debug, Clone, Default)]
+pub struct ZipStorage {
+    files: MemoryStorage,
+}
+
+impl ZipStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn open<R: Read + Seek>(reader: R) -> io::Result<Self> {
+        let mut archive = ZipArchive::new(reader)?;
+        let mut files = MemoryStorage::new();
+
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+
+            if file.is_file() {
+                let mut data = vec![];
+                file.read_to_end(&mut data)?;
+                files.write(file.name(), &data)?;
+            }
+        }
+
+        Ok(Self { files })
+    }
+
+    pub fn write_to<W: Write + Seek>(&self, writer: W) -> io::Result<()> {
+        let mut archive = ZipWriter::new(writer);
+
+        for name in self.files.files()? {
+            if let Some(data) = self.files.read(&name)? {
+                archive.start_file(name, FileOptions::default())?;
+                archive.write_all(&data)?;
+            }
+        }
+
+        archive.finish()?;
+        Ok(())
+    }
+}
+
+impl Storage for ZipStorage {
+    fn read(&self, name: &str) -> io::Result<Option<Vec<u8>>> {
+        self.files.read(name)
+    }
+
+    fn write(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        self.files.write(name, data)
+    }
+
+    fn remove(&mut self, name: &str) -> io::Result<()> {
+        self.files.remove(name)
+    }
+
+    fn files(&self) -> io::Result<Vec<String>> {
+        self.files.files()
+    }
+}
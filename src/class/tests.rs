@@ -0,0 +1,240 @@
+use super::{
+    cybergrind::CybergrindData,
+    difficulty::DifficultyData,
+    general::GeneralData,
+    level::LevelData,
+    storage::{MemoryStorage, Storage},
+    traits::{
+        FieldMap, IntoFileInfix, LoadableSavable, ParsableClass, ParsableClassKeyed, LIBRARY_NAME,
+    },
+};
+use crate::enums::{Difficulty, Level};
+use ms_nrbf::{Class, Field, PrimitiveArray, Stream};
+use std::{collections::BTreeMap, io::Cursor};
+
+fn encode(class_name: &str, fields: FieldMap) -> Vec<u8> {
+    let mut data = vec![];
+
+    Stream {
+        root: Class {
+            library_name: LIBRARY_NAME.to_string(),
+            name: class_name.to_string(),
+            fields,
+        },
+    }
+    .encode(&mut data)
+    .unwrap();
+
+    data
+}
+
+/// Decodes a file, parses and unparses it, and checks that encoding it again
+/// gives back the same bytes.
+fn assert_round_trip<P>(class_name: &str, fields: FieldMap, parse: P)
+where
+    P: Fn(&Class) -> Option<FieldMap>,
+{
+    let data = encode(class_name, fields);
+    let class = Stream::decode(&mut Cursor::new(&data)).unwrap().root;
+    let fields = parse(&class).expect("class should parse");
+
+    assert_eq!(encode(class_name, fields), data);
+}
+
+fn decode(data: &[u8]) -> FieldMap {
+    Stream::decode(&mut Cursor::new(data)).unwrap().root.fields
+}
+
+fn int32_array<'a>(fields: &'a mut FieldMap, name: &str) -> &'a mut Vec<i32> {
+    match fields.get_mut(name) {
+        Some(Field::PrimitiveArray(PrimitiveArray::Int32(values))) => values,
+        _ => panic!("{} should be an Int32 array", name),
+    }
+}
+
+fn bool_array<'a>(fields: &'a mut FieldMap, name: &str) -> &'a mut Vec<bool> {
+    match fields.get_mut(name) {
+        Some(Field::PrimitiveArray(PrimitiveArray::Boolean(values))) => values,
+        _ => panic!("{} should be a Boolean array", name),
+    }
+}
+
+#[test]
+fn general_round_trip() {
+    let mut fields = GeneralData::default().unparse().unwrap();
+    // Entries from newer game versions, including the gap in the enemy indexes.
+    int32_array(&mut fields, "secretMissions").extend([2, 1]);
+    let enemies = int32_array(&mut fields, "newEnemiesFound");
+    enemies[36] = 1;
+    enemies.extend([2, 1]);
+    bool_array(&mut fields, "unlockablesFound").extend([true, false, true]);
+
+    assert_round_trip(GeneralData::CLASS_NAME, fields, |class| {
+        GeneralData::parse(class)?.unparse()
+    });
+}
+
+#[test]
+fn level_round_trip() {
+    let level = Level::IntoTheFire;
+    let mut fields = LevelData::create_new(&level).unparse(&level).unwrap();
+    int32_array(&mut fields, "ranks")[2] = 12;
+    bool_array(&mut fields, "secretsFound")[0] = true;
+
+    assert_round_trip(LevelData::CLASS_NAME, fields, |class| {
+        LevelData::parse(class)?.unparse(&level)
+    });
+}
+
+#[test]
+fn difficulty_round_trip() {
+    let difficulty = Difficulty::Violent;
+    let mut fields = DifficultyData::create_new(&difficulty)
+        .unparse(&difficulty)
+        .unwrap();
+    int32_array(&mut fields, "primeLevels")[1] = 2;
+
+    assert_round_trip(DifficultyData::CLASS_NAME, fields, |class| {
+        DifficultyData::parse(class)?.unparse(&difficulty)
+    });
+}
+
+#[test]
+fn cybergrind_round_trip() {
+    let cybergrind = CybergrindData {
+        wave: "21".to_string(),
+        waves: vec!["21.5".to_string(); 6],
+        ..Default::default()
+    };
+
+    assert_round_trip(
+        CybergrindData::CLASS_NAME,
+        cybergrind.unparse().unwrap(),
+        |class| CybergrindData::parse(class)?.unparse(),
+    );
+}
+
+/// A general file with entries from a newer game version and a missing field.
+fn general_with_extra_entries() -> FieldMap {
+    let mut fields = GeneralData::default().unparse().unwrap();
+    int32_array(&mut fields, "secretMissions").extend([2, 1]);
+    int32_array(&mut fields, "newEnemiesFound").extend([2, 1]);
+    bool_array(&mut fields, "unlockablesFound").extend([true, false, true]);
+    fields.shift_remove("money");
+
+    fields
+}
+
+#[test]
+fn repair_keeps_extra_entries() {
+    let file = GeneralData::FILE_NAME;
+    let original = general_with_extra_entries();
+    let mut storage = MemoryStorage::new();
+    storage
+        .write(file, &encode(GeneralData::CLASS_NAME, original.clone()))
+        .unwrap();
+
+    let repairs = GeneralData::repair(&mut storage).unwrap();
+    assert_eq!(repairs.len(), 1);
+    assert_eq!(repairs[0].changes, ["money: missing, set to default"]);
+
+    let mut fields = decode(&storage.read(file).unwrap().unwrap());
+    let mut original = original;
+    for name in ["secretMissions", "newEnemiesFound"] {
+        assert_eq!(
+            int32_array(&mut fields, name),
+            int32_array(&mut original, name)
+        );
+    }
+    assert_eq!(
+        bool_array(&mut fields, "unlockablesFound"),
+        bool_array(&mut original, "unlockablesFound")
+    );
+}
+
+#[test]
+fn repair_pads_short_arrays() {
+    let file = GeneralData::FILE_NAME;
+    let mut fields = GeneralData::default().unparse().unwrap();
+    int32_array(&mut fields, "secretMissions").truncate(2);
+    let mut storage = MemoryStorage::new();
+    storage
+        .write(file, &encode(GeneralData::CLASS_NAME, fields))
+        .unwrap();
+
+    let repairs = GeneralData::repair(&mut storage).unwrap();
+    assert_eq!(repairs.len(), 1);
+    assert!(repairs[0].changes[0].starts_with("secretMissions: padded from 2 to"));
+
+    let mut fields = decode(&storage.read(file).unwrap().unwrap());
+    let mut default = GeneralData::default().unparse().unwrap();
+    assert_eq!(
+        int32_array(&mut fields, "secretMissions").len(),
+        int32_array(&mut default, "secretMissions").len()
+    );
+}
+
+#[test]
+fn repair_resets_wrong_types() {
+    let level = Level::IntoTheFire;
+    let mut fields = LevelData::create_new(&level).unparse(&level).unwrap();
+    fields.insert(
+        "ranks".to_string(),
+        Field::PrimitiveArray(PrimitiveArray::Boolean(vec![true; 6])),
+    );
+    let mut storage = MemoryStorage::new();
+    let file = format!(
+        "{}{}{}",
+        LevelData::FILE_PREFIX,
+        level.into_file_infix(),
+        LevelData::FILE_SUFFIX
+    );
+    storage
+        .write(&file, &encode(LevelData::CLASS_NAME, fields))
+        .unwrap();
+
+    let repairs = <BTreeMap<Level, LevelData>>::repair(&mut storage).unwrap();
+    assert_eq!(repairs.len(), 1);
+    assert_eq!(repairs[0].changes, ["ranks: wrong type, set to default"]);
+}
+
+#[test]
+fn repair_never_overwrites_backups() {
+    let file = GeneralData::FILE_NAME;
+    let mut storage = MemoryStorage::new();
+    storage.write(&format!("{}.bak", file), b"older").unwrap();
+
+    for backup in [format!("{}.1.bak", file), format!("{}.2.bak", file)] {
+        storage
+            .write(
+                file,
+                &encode(GeneralData::CLASS_NAME, general_with_extra_entries()),
+            )
+            .unwrap();
+
+        let repairs = GeneralData::repair(&mut storage).unwrap();
+        assert_eq!(repairs[0].backup.as_deref(), Some(backup.as_str()));
+    }
+    assert_eq!(
+        storage.read(&format!("{}.bak", file)).unwrap().unwrap(),
+        b"older"
+    );
+}
+
+#[test]
+fn repair_undecodable_file() {
+    let file = GeneralData::FILE_NAME;
+    let mut storage = MemoryStorage::new();
+    storage.write(file, b"not a save").unwrap();
+
+    let repairs = GeneralData::repair(&mut storage).unwrap();
+    assert!(repairs[0].is_repaired());
+    assert_eq!(
+        storage.read(&format!("{}.bak", file)).unwrap().unwrap(),
+        b"not a save"
+    );
+    let class = Stream::decode(&mut Cursor::new(storage.read(file).unwrap().unwrap()))
+        .unwrap()
+        .root;
+    assert!(GeneralData::parse(&class).is_some());
+}
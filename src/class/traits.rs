@@ -1,11 +1,13 @@
+use super::{
+    repair::{repair_fields, FileRepair, BACKUP_EXTENSION},
+    storage::Storage,
+};
 use indexmap::IndexMap;
 use ms_nrbf::{Class, Field, Stream};
 use std::{
     collections::BTreeMap,
     fmt,
-    fs::{remove_file, File},
-    io,
-    path::Path,
+    io::{self, Cursor},
     str::FromStr,
 };
 use strum::IntoEnumIterator;
@@ -19,6 +21,18 @@ pub trait IntoFileInfix {
     fn into_file_infix(&self) -> Box<dyn fmt::Display>;
 }
 
+pub trait FieldName {
+    fn field_name(&self) -> &'static str;
+}
+
+pub trait ArrayIndex
+where
+    Self: Sized,
+{
+    fn from_index(index: usize) -> Option<Self>;
+    fn index(&self) -> usize;
+}
+
 pub(super) trait ParsableClass
 where
     Self: Sized + Default,
@@ -45,69 +59,152 @@ where
 }
 
 pub(super) trait LoadableSavable {
-    fn load<P: AsRef<Path>>(save_path: P) -> Self;
-    fn save<P: AsRef<Path>>(&self, save_path: P) -> Result<(), io::Error>;
+    fn load<S: Storage>(storage: &S) -> Self;
+    fn save<S: Storage>(&self, storage: &mut S) -> Result<(), io::Error>;
+    fn repair<S: Storage>(storage: &mut S) -> Result<Vec<FileRepair>, io::Error>;
 }
 
-impl<T: ParsableClass> LoadableSavable for T {
-    fn load<P: AsRef<Path>>(save_path: P) -> Self {
-        let path = save_path.as_ref().join(Self::FILE_NAME);
+/// `<file>.bak`, or `<file>.1.bak` and so on if earlier backups exist, so no
+/// original is ever overwritten.
+fn get_backup_name(storage: &impl Storage, name: &str) -> Result<String, io::Error> {
+    let files = storage.files()?;
+    let mut backup = format!("{}{}", name, BACKUP_EXTENSION);
 
-        if let Ok(mut file) = File::open(path) {
-            if let Ok(stream) = Stream::decode(&mut file) {
-                if let Some(value) = Self::parse(&stream.root) {
-                    return value;
-                }
+    for i in 1.. {
+        if !files.contains(&backup) {
+            break;
+        }
+        backup = format!("{}.{}{}", name, i, BACKUP_EXTENSION);
+    }
+
+    Ok(backup)
+}
+
+fn decode_class(storage: &impl Storage, name: &str) -> Option<Class> {
+    let data = storage.read(name).ok()??;
+    let stream = Stream::decode(&mut Cursor::new(data)).ok()?;
+
+    Some(stream.root)
+}
+
+/// Rewrites a save file from the fields that can still be read, keeping the
+/// original next to it. Files that are missing or need no changes are left
+/// alone, and files that can't be rebuilt are reported without a backup.
+fn repair_class<F, P>(
+    storage: &mut impl Storage,
+    name: &str,
+    class_name: &str,
+    template: F,
+    parse: P,
+) -> Result<Option<FileRepair>, io::Error>
+where
+    F: Fn() -> Option<FieldMap>,
+    P: Fn(&Class) -> Option<FieldMap>,
+{
+    let Some(data) = storage.read(name)? else {
+        return Ok(None);
+    };
+
+    let decoded = Stream::decode(&mut Cursor::new(&data))
+        .ok()
+        .map(|stream| stream.root);
+    let Some((fields, changes)) = repair_fields(decoded, template, parse) else {
+        return Ok(Some(FileRepair {
+            file: name.to_string(),
+            backup: None,
+            changes: vec!["the editor can't rebuild this file".to_string()],
+        }));
+    };
+
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    let backup = get_backup_name(storage, name)?;
+    storage.write(&backup, &data)?;
+    encode_class(storage, name, class_name, fields)?;
+
+    Ok(Some(FileRepair {
+        file: name.to_string(),
+        backup: Some(backup),
+        changes,
+    }))
+}
+
+fn encode_class(
+    storage: &mut impl Storage,
+    name: &str,
+    class_name: &str,
+    fields: FieldMap,
+) -> Result<(), io::Error> {
+    let mut data = vec![];
+
+    Stream {
+        root: Class {
+            library_name: LIBRARY_NAME.to_string(),
+            name: class_name.to_string(),
+            fields,
+        },
+    }
+    .encode(&mut data)?;
+
+    storage.write(name, &data)
+}
+
+impl<T: ParsableClass> LoadableSavable for T {
+    fn load<S: Storage>(storage: &S) -> Self {
+        if let Some(class) = decode_class(storage, Self::FILE_NAME) {
+            if let Some(value) = Self::parse(&class) {
+                return value;
             }
         }
 
         Self::default()
     }
 
-    fn save<P: AsRef<Path>>(&self, save_path: P) -> Result<(), io::Error> {
-        let path = save_path.as_ref().join(Self::FILE_NAME);
-
+    fn save<S: Storage>(&self, storage: &mut S) -> Result<(), io::Error> {
         if self.get_file_exists() {
             if let Some(fields) = self.unparse() {
-                let mut file = File::create(path)?;
-
-                Stream {
-                    root: Class {
-                        library_name: LIBRARY_NAME.to_string(),
-                        name: Self::CLASS_NAME.to_string(),
-                        fields,
-                    },
-                }
-                .encode(&mut file)?;
+                encode_class(storage, Self::FILE_NAME, Self::CLASS_NAME, fields)?;
             }
-        } else if path.exists() {
-            remove_file(path)?;
+        } else {
+            storage.remove(Self::FILE_NAME)?;
         }
 
         Ok(())
     }
+
+    fn repair<S: Storage>(storage: &mut S) -> Result<Vec<FileRepair>, io::Error> {
+        Ok(repair_class(
+            storage,
+            Self::FILE_NAME,
+            Self::CLASS_NAME,
+            || Self::default().unparse(),
+            |class| Self::parse(class)?.unparse(),
+        )?
+        .into_iter()
+        .collect())
+    }
 }
 
 impl<V: FromStr + IntoEnumIterator + Ord + IntoFileInfix, T: ParsableClassKeyed<V>> LoadableSavable
     for BTreeMap<V, T>
 {
-    fn load<P: AsRef<Path>>(save_path: P) -> BTreeMap<V, T> {
+    fn load<S: Storage>(storage: &S) -> BTreeMap<V, T> {
         let mut map = BTreeMap::new();
 
         for variant in V::iter() {
-            let path = save_path.as_ref().join(format!(
+            let name = format!(
                 "{}{}{}",
                 T::FILE_PREFIX,
                 variant.into_file_infix(),
                 T::FILE_SUFFIX
-            ));
-
-            if let Ok(mut file) = File::open(path) {
-                if let Ok(stream) = Stream::decode(&mut file) {
-                    if let Some(value) = T::parse(&stream.root) {
-                        map.insert(variant, value);
-                        continue;
-                    }
+            );
+
+            if let Some(class) = decode_class(storage, &name) {
+                if let Some(value) = T::parse(&class) {
+                    map.insert(variant, value);
+                    continue;
                 }
             }
 
@@ -118,33 +215,47 @@ impl<V: FromStr + IntoEnumIterator + Ord + IntoFileInfix, T: ParsableClassKeyed<
         map
     }
 
-    fn save<P: AsRef<Path>>(&self, save_path: P) -> Result<(), io::Error> {
+    fn save<S: Storage>(&self, storage: &mut S) -> Result<(), io::Error> {
         for (key, value) in self {
-            let path = save_path.as_ref().join(format!(
+            let name = format!(
                 "{}{}{}",
                 T::FILE_PREFIX,
                 key.into_file_infix(),
                 T::FILE_SUFFIX
-            ));
+            );
 
             if value.get_file_exists() {
-                if let Some(fields) = value.unparse(&key) {
-                    let mut file = File::create(path)?;
-
-                    Stream {
-                        root: Class {
-                            library_name: LIBRARY_NAME.to_string(),
-                            name: T::CLASS_NAME.to_string(),
-                            fields,
-                        },
-                    }
-                    .encode(&mut file)?;
+                if let Some(fields) = value.unparse(key) {
+                    encode_class(storage, &name, T::CLASS_NAME, fields)?;
                 }
-            } else if path.exists() {
-                remove_file(path)?;
+            } else {
+                storage.remove(&name)?;
             }
         }
 
         Ok(())
     }
+
+    fn repair<S: Storage>(storage: &mut S) -> Result<Vec<FileRepair>, io::Error> {
+        let mut repairs = vec![];
+
+        for variant in V::iter() {
+            let name = format!(
+                "{}{}{}",
+                T::FILE_PREFIX,
+                variant.into_file_infix(),
+                T::FILE_SUFFIX
+            );
+
+            repairs.extend(repair_class(
+                storage,
+                &name,
+                T::CLASS_NAME,
+                || T::create_new(&variant).unparse(&variant),
+                |class| T::parse(class)?.unparse(&variant),
+            )?);
+        }
+
+        Ok(repairs)
+    }
 }
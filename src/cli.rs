@@ -0,0 +1,703 @@
+use clap::{Parser, Subcommand};
+use strum::IntoEnumIterator;
+use std::{
+    fs::{create_dir_all, write, File},
+    io::{stdin, stdout, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+use ultrakill_save_editor::{
+    archive::{export_slot, SlotArchive},
+    bulk::{parse_difficulty, parse_level, BulkAction, BulkEdit, Scope},
+    preset::{get_presets_path, load_presets},
+    progression::Progression,
+    class::{
+        classes::Classes,
+        repair::FileRepair,
+        storage::{DirectoryStorage, MemoryStorage},
+        traits::FieldName,
+    },
+    enums::{Difficulty, Level, LevelRank, Lockable, UnlockableWeaponVariant},
+    saves::{
+        add_bookmark, detect_saves_root, discover_slots, get_slot_name, get_slot_summaries,
+        load_bookmarks, remove_bookmark, resolve_slot, Slot, SlotOperation, SlotSummary,
+    },
+    report::{Report, ReportFormat},
+    shop::{buy, get_buyable, get_spent, refund},
+    stats::{Stats, COMPLETION_COLUMNS},
+    validate::validate,
+};
+
+#[derive(Parser)]
+#[command(version, about = "Command line interface for the ULTRAKILL save editor")]
+struct Cli {
+    /// Save slot directory to use instead of auto-detecting one
+    #[arg(long, global = true)]
+    path: Option<PathBuf>,
+    /// Save slot to use, by number, name, bookmark or path
+    #[arg(long, global = true, default_value = "1")]
+    slot: String,
+    /// Saves directory containing the slots, instead of auto-detecting it
+    #[arg(long, global = true)]
+    saves: Option<PathBuf>,
+    /// Don't ask for confirmation before changing slots
+    #[arg(long, short, global = true)]
+    yes: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Package every save file in the slot into one archive
+    Export { archive: PathBuf },
+    /// Replace the slot's save files with the contents of an archive
+    Import { archive: PathBuf },
+    /// Replace the save files of one slot with those of another
+    Copy { source: String, target: String },
+    /// Exchange the save files of two slots
+    Swap { first: String, second: String },
+    /// Delete every save file in a slot
+    Clear { target: String },
+    /// Summarise the progress stored in every slot
+    Overview,
+    /// Apply one edit to every level in a scope
+    Bulk {
+        #[command(subcommand)]
+        action: BulkCommand,
+        /// Levels to edit: all, act:<N>, layer:<N> or level:<N-N>
+        #[arg(long, global = true, default_value = "all")]
+        scope: Scope,
+        /// Difficulty to edit ranks and assists on, by name or number, instead of every difficulty
+        #[arg(long, global = true, value_parser = parse_difficulty_arg)]
+        difficulty: Option<Difficulty>,
+    },
+    /// List the available presets, or replace the slot's progress with one
+    Preset {
+        /// Name of the preset to apply
+        name: Option<String>,
+    },
+    /// Make the slot look as if every level up to LEVEL was played in order
+    Progress {
+        /// Last level played, such as 4-2
+        #[arg(value_parser = parse_level_arg)]
+        level: Level,
+        /// Difficulty played on, by name or number
+        #[arg(long, value_parser = parse_difficulty_arg, default_value = "standard")]
+        difficulty: Difficulty,
+        /// Rank every level was finished with
+        #[arg(long, value_parser = parse_rank, default_value = "s")]
+        rank: LevelRank,
+    },
+    /// List every discovered and bookmarked slot
+    Slots,
+    /// Print completion statistics for the slot
+    Stats,
+    /// Look for contradictions in the slot, exiting with an error if any are found
+    Check,
+    /// Rewrite malformed save files, keeping the originals as .bak files
+    Repair,
+    /// List what the shop sells, or buy or refund a weapon variant
+    Shop {
+        #[command(subcommand)]
+        command: Option<ShopCommand>,
+    },
+    /// Write a completion report for the slot
+    Report {
+        output: PathBuf,
+        /// Report format (md, html or csv), instead of using the file extension
+        #[arg(long)]
+        format: Option<ReportFormat>,
+    },
+    /// Add or remove slot directories outside the saves directory
+    Bookmark {
+        #[command(subcommand)]
+        command: BookmarkCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum BulkCommand {
+    /// Set the rank of every level (none, d, c, b, a, s or p)
+    SetRank {
+        #[arg(value_parser = parse_rank)]
+        rank: LevelRank,
+    },
+    /// Mark every secret as found
+    FindSecrets,
+    /// Mark every challenge as completed
+    CompleteChallenges,
+    /// Mark every challenge as not completed
+    ResetChallenges,
+    /// Remove the major assists mark from every level
+    ClearAssists,
+    /// Create the save file of every level
+    CreateFiles,
+    /// Delete the save file of every level
+    DeleteFiles,
+    /// Set the secret mission of every layer (locked, unlocked or completed)
+    SecretMissions {
+        #[arg(value_parser = parse_lockable)]
+        state: Lockable,
+    },
+    /// Set the bestiary entry of every enemy first met in the scope (locked, unlocked or completed)
+    Bestiary {
+        #[arg(value_parser = parse_lockable)]
+        state: Lockable,
+    },
+}
+
+impl BulkCommand {
+    fn get_action(&self) -> BulkAction {
+        match self {
+            Self::SetRank { rank } => BulkAction::SetRank(*rank),
+            Self::FindSecrets => BulkAction::FindSecrets,
+            Self::CompleteChallenges => BulkAction::SetChallenge(true),
+            Self::ResetChallenges => BulkAction::SetChallenge(false),
+            Self::ClearAssists => BulkAction::ClearMajorAssists,
+            Self::CreateFiles => BulkAction::CreateFiles,
+            Self::DeleteFiles => BulkAction::DeleteFiles,
+            Self::SecretMissions { state } => BulkAction::SetSecretMissions(*state),
+            Self::Bestiary { state } => BulkAction::SetBestiary(*state),
+        }
+    }
+}
+
+fn parse_level_arg(value: &str) -> Result<Level, String> {
+    parse_level(value).ok_or_else(|| format!("unknown level {}", value))
+}
+
+fn parse_difficulty_arg(value: &str) -> Result<Difficulty, String> {
+    parse_difficulty(value).ok_or_else(|| format!("unknown difficulty {}", value))
+}
+
+fn parse_rank(value: &str) -> Result<LevelRank, String> {
+    LevelRank::iter()
+        .find(|rank| rank.to_string().eq_ignore_ascii_case(value))
+        .ok_or_else(|| format!("unknown rank {}", value))
+}
+
+fn parse_lockable(value: &str) -> Result<Lockable, String> {
+    [Lockable::Locked, Lockable::Unlocked, Lockable::Completed]
+        .into_iter()
+        .find(|state| state.to_string().eq_ignore_ascii_case(value))
+        .ok_or_else(|| format!("unknown state {}", value))
+}
+
+#[derive(Subcommand)]
+enum ShopCommand {
+    /// Unlock a variant and take its price from the money
+    Buy {
+        /// Variant name, such as "marksman revolver", or save field, such as rev2
+        #[arg(value_parser = parse_variant)]
+        variant: UnlockableWeaponVariant,
+    },
+    /// Lock a variant and give its price back
+    Refund {
+        /// Variant name, such as "marksman revolver", or save field, such as rev2
+        #[arg(value_parser = parse_variant)]
+        variant: UnlockableWeaponVariant,
+    },
+}
+
+fn parse_variant(value: &str) -> Result<UnlockableWeaponVariant, String> {
+    UnlockableWeaponVariant::iter()
+        .find(|variant| {
+            variant.to_string().eq_ignore_ascii_case(value)
+                || variant.field_name().eq_ignore_ascii_case(value)
+        })
+        .ok_or_else(|| format!("unknown weapon variant {}", value))
+}
+
+#[derive(Subcommand)]
+enum BookmarkCommand {
+    /// Bookmark a slot directory
+    Add { path: PathBuf },
+    /// Remove a bookmarked slot directory
+    Remove { path: PathBuf },
+}
+
+fn get_saves_root(cli: &Cli) -> Option<PathBuf> {
+    cli.saves.clone().or_else(detect_saves_root)
+}
+
+fn get_slots(cli: &Cli) -> Vec<Slot> {
+    discover_slots(get_saves_root(cli).as_deref(), &load_bookmarks())
+}
+
+fn get_slot_path(cli: &Cli, slot: &str) -> Result<PathBuf, String> {
+    resolve_slot(get_saves_root(cli).as_deref(), &get_slots(cli), slot)
+        .ok_or_else(|| format!("unknown save slot {}", slot))
+}
+
+fn get_save_path(cli: &Cli) -> Result<PathBuf, String> {
+    let path = get_target_path(cli)?;
+
+    match path.is_dir() {
+        true => Ok(path),
+        false => Err(format!("{} is not a directory", path.display())),
+    }
+}
+
+/// Like `get_save_path`, but the slot directory doesn't have to exist yet.
+fn get_target_path(cli: &Cli) -> Result<PathBuf, String> {
+    let path = match &cli.path {
+        Some(path) => path.clone(),
+        None => get_slot_path(cli, &cli.slot)?,
+    };
+
+    match path.exists() && !path.is_dir() {
+        true => Err(format!("{} is not a directory", path.display())),
+        false => Ok(path),
+    }
+}
+
+fn confirm(cli: &Cli) -> Result<bool, String> {
+    if cli.yes {
+        return Ok(true);
+    }
+
+    print!("Continue? [y/N] ");
+    stdout().flush().map_err(|error| error.to_string())?;
+
+    let mut answer = String::new();
+    stdin()
+        .read_line(&mut answer)
+        .map_err(|error| error.to_string())?;
+
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+fn run_slot_operation(cli: &Cli, operation: SlotOperation) -> Result<(), String> {
+    println!("{}", operation);
+    for (name, summary) in operation
+        .get_summaries()
+        .map_err(|error| error.to_string())?
+    {
+        println!("  {}: {}", name, summary);
+    }
+
+    if !confirm(cli)? {
+        println!("Cancelled");
+        return Ok(());
+    }
+
+    operation.apply().map_err(|error| error.to_string())?;
+
+    println!("Done");
+    for (name, summary) in operation
+        .get_summaries()
+        .map_err(|error| error.to_string())?
+    {
+        println!("  {}: {}", name, summary);
+    }
+
+    Ok(())
+}
+
+fn print_overview_row<F: Fn(&SlotSummary) -> String>(
+    label: &str,
+    summaries: &[(Slot, SlotSummary)],
+    value: F,
+) {
+    print!("{:<32}", label);
+    for (_, summary) in summaries {
+        print!("{:>12}", value(summary));
+    }
+    println!();
+}
+
+fn print_overview(summaries: &[(Slot, SlotSummary)]) {
+    print!("{:<32}", "");
+    for (slot, _) in summaries {
+        print!("{:>12}", slot.name);
+    }
+    println!();
+
+    print_overview_row("Files", summaries, |summary| summary.files.to_string());
+    for difficulty in Difficulty::iter() {
+        print_overview_row(
+            &format!("Levels completed ({})", difficulty),
+            summaries,
+            |summary| {
+                summary
+                    .levels_completed_by_difficulty
+                    .get(&difficulty)
+                    .copied()
+                    .unwrap_or_default()
+                    .to_string()
+            },
+        );
+    }
+    print_overview_row("P-ranks", summaries, |summary| summary.p_ranks.to_string());
+    print_overview_row("Secrets found", summaries, |summary| {
+        summary.secrets_found.to_string()
+    });
+    print_overview_row("Primes beaten", summaries, |summary| {
+        summary.primes_beaten.to_string()
+    });
+    print_overview_row("Weapons unlocked", summaries, |summary| {
+        summary.weapons_unlocked.to_string()
+    });
+    print_overview_row("Money", summaries, |summary| {
+        match summary.money.is_empty() {
+            true => "-".to_string(),
+            false => format!("{}P", summary.money),
+        }
+    });
+    print_overview_row("Best cybergrind wave", summaries, |summary| {
+        match summary.best_wave {
+            Some(wave) => format!("{:.2}", wave),
+            None => "-".to_string(),
+        }
+    });
+}
+
+fn load_classes(cli: &Cli) -> Result<Classes, String> {
+    let save_path = get_save_path(cli)?;
+
+    Classes::load(&DirectoryStorage::new(&save_path))
+        .ok_or_else(|| format!("failed to load {}", save_path.display()))
+}
+
+fn print_stats(stats: &Stats) {
+    print!("{:<40}", "");
+    for column in COMPLETION_COLUMNS {
+        print!("{:>20}", column);
+    }
+    println!();
+
+    for (label, completion) in stats.groups() {
+        print!("{:<40}", label);
+        for progress in completion.columns() {
+            print!("{:>20}", progress.to_string());
+        }
+        println!();
+    }
+
+    println!();
+    for (label, progress) in stats.totals() {
+        println!("{:<40}{:>20}", label, progress.to_string());
+    }
+}
+
+fn print_repairs(repairs: &[FileRepair]) {
+    for repair in repairs {
+        println!("  {}", repair);
+        for change in &repair.changes {
+            println!("    {}", change);
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    match &cli.command {
+        Command::Export { archive } => {
+            let save_path = get_save_path(&cli)?;
+            let file = File::create(archive).map_err(|error| error.to_string())?;
+            let manifest = export_slot(
+                &DirectoryStorage::new(&save_path),
+                &get_slot_name(&save_path),
+                file,
+            )
+            .map_err(|error| error.to_string())?;
+
+            println!(
+                "Exported {} files from {} to {}",
+                manifest.files.len(),
+                save_path.display(),
+                archive.display()
+            );
+        }
+        Command::Import { archive } => {
+            let save_path = get_target_path(&cli)?;
+            let file = File::open(archive).map_err(|error| error.to_string())?;
+            let slot_archive = SlotArchive::open(file).map_err(|error| error.to_string())?;
+
+            println!("Import {} into {}", archive.display(), save_path.display());
+            println!(
+                "  Archive of {}: {}",
+                slot_archive.manifest.source_slot,
+                slot_archive
+                    .get_summary()
+                    .map_err(|error| error.to_string())?
+            );
+            println!(
+                "  {}: {}",
+                get_slot_name(&save_path),
+                SlotSummary::load(&DirectoryStorage::new(&save_path))
+                    .map_err(|error| error.to_string())?
+            );
+            if !confirm(&cli)? {
+                println!("Cancelled");
+                return Ok(());
+            }
+
+            create_dir_all(&save_path).map_err(|error| error.to_string())?;
+            slot_archive
+                .import_into(&mut DirectoryStorage::new(&save_path))
+                .map_err(|error| error.to_string())?;
+
+            println!(
+                "Imported {} files exported from {} by editor v{} into {}",
+                slot_archive.manifest.files.len(),
+                slot_archive.manifest.source_slot,
+                slot_archive.manifest.editor_version,
+                save_path.display()
+            );
+        }
+        Command::Copy { source, target } => run_slot_operation(
+            &cli,
+            SlotOperation::Copy {
+                source: get_slot_path(&cli, source)?,
+                target: get_slot_path(&cli, target)?,
+            },
+        )?,
+        Command::Swap { first, second } => run_slot_operation(
+            &cli,
+            SlotOperation::Swap(
+                get_slot_path(&cli, first)?,
+                get_slot_path(&cli, second)?,
+            ),
+        )?,
+        Command::Clear { target } => run_slot_operation(
+            &cli,
+            SlotOperation::Clear(get_slot_path(&cli, target)?),
+        )?,
+        Command::Overview => print_overview(
+            &get_slot_summaries(&get_slots(&cli)).map_err(|error| error.to_string())?,
+        ),
+        Command::Bulk {
+            action,
+            scope,
+            difficulty,
+        } => {
+            let save_path = get_save_path(&cli)?;
+            let mut classes = load_classes(&cli)?;
+            let bulk_edit = BulkEdit {
+                action: action.get_action(),
+                scope: *scope,
+                difficulty: *difficulty,
+            };
+
+            println!("{} in {}", bulk_edit, save_path.display());
+            if !confirm(&cli)? {
+                println!("Cancelled");
+                return Ok(());
+            }
+
+            let count = bulk_edit.apply(&mut classes);
+            classes
+                .save(&mut DirectoryStorage::new(&save_path))
+                .map_err(|error| error.to_string())?;
+            println!("Done, {} changed", count);
+        }
+        Command::Preset { name: None } => {
+            for preset in load_presets().map_err(|error| error.to_string())? {
+                println!("{:<24}{}", preset.name, preset.description);
+            }
+
+            if let Some(path) = get_presets_path() {
+                println!();
+                println!("User presets are read from {}", path.display());
+            }
+        }
+        Command::Preset { name: Some(name) } => {
+            let presets = load_presets().map_err(|error| error.to_string())?;
+            let preset = presets
+                .iter()
+                .find(|preset| preset.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| format!("unknown preset {}", name))?;
+            let save_path = get_save_path(&cli)?;
+
+            println!(
+                "Replace progress in {} with preset {}, keeping cybergrind bests",
+                save_path.display(),
+                preset.name
+            );
+            if !confirm(&cli)? {
+                println!("Cancelled");
+                return Ok(());
+            }
+
+            let mut classes = load_classes(&cli)?;
+            preset.apply(&mut classes);
+            classes
+                .save(&mut DirectoryStorage::new(&save_path))
+                .map_err(|error| error.to_string())?;
+            println!("Done");
+        }
+        Command::Progress {
+            level,
+            difficulty,
+            rank,
+        } => {
+            let save_path = get_save_path(&cli)?;
+            let mut classes = load_classes(&cli)?;
+            let progression = Progression {
+                level: *level,
+                difficulty: *difficulty,
+                rank: *rank,
+            };
+
+            println!("{} in {}", progression, save_path.display());
+            if !confirm(&cli)? {
+                println!("Cancelled");
+                return Ok(());
+            }
+
+            progression.apply(&mut classes);
+            classes
+                .save(&mut DirectoryStorage::new(&save_path))
+                .map_err(|error| error.to_string())?;
+            println!("Done");
+        }
+        Command::Stats => print_stats(&Stats::new(&load_classes(&cli)?)),
+        Command::Repair => {
+            let save_path = get_save_path(&cli)?;
+            let mut preview = MemoryStorage::copy_from(&DirectoryStorage::new(&save_path))
+                .map_err(|error| error.to_string())?;
+            let repairs = Classes::repair(&mut preview).map_err(|error| error.to_string())?;
+
+            if repairs.is_empty() {
+                println!("Nothing to repair in {}", save_path.display());
+                return Ok(());
+            }
+
+            println!("Repair {}", save_path.display());
+            print_repairs(&repairs);
+            if !repairs.iter().any(FileRepair::is_repaired) {
+                println!("None of these files can be repaired");
+                return Ok(());
+            }
+            if !confirm(&cli)? {
+                println!("Cancelled");
+                return Ok(());
+            }
+
+            Classes::repair(&mut DirectoryStorage::new(&save_path))
+                .map_err(|error| error.to_string())?;
+            println!("Done");
+        }
+        Command::Shop { command: None } => {
+            let classes = load_classes(&cli)?;
+            let general = &classes.general;
+
+            for variant in UnlockableWeaponVariant::iter() {
+                let Some(price) = variant.get_price() else {
+                    continue;
+                };
+
+                println!(
+                    "{:<40}{:>10}P  {}",
+                    variant.to_string(),
+                    price,
+                    match general.unlocked_weapons.get(&variant) == Some(&true) {
+                        true => "unlocked",
+                        false => "locked",
+                    }
+                );
+            }
+
+            println!();
+            println!("Money: {}P", general.money);
+            println!("Spent: {}P", get_spent(general));
+            println!(
+                "Left to buy: {}P",
+                get_buyable(general)
+                    .iter()
+                    .filter_map(|variant| variant.get_price())
+                    .sum::<u32>()
+            );
+        }
+        Command::Shop {
+            command: Some(command),
+        } => {
+            let save_path = get_save_path(&cli)?;
+            let mut classes = load_classes(&cli)?;
+            let (variant, result) = match command {
+                ShopCommand::Buy { variant } => (variant, buy(&mut classes.general, variant)),
+                ShopCommand::Refund { variant } => {
+                    (variant, refund(&mut classes.general, variant))
+                }
+            };
+            result.map_err(|error| error.to_string())?;
+
+            println!(
+                "{} {} in {}, leaving {}P",
+                match command {
+                    ShopCommand::Buy { .. } => "Buy",
+                    ShopCommand::Refund { .. } => "Refund",
+                },
+                variant,
+                save_path.display(),
+                classes.general.money
+            );
+            if !confirm(&cli)? {
+                println!("Cancelled");
+                return Ok(());
+            }
+
+            classes
+                .save(&mut DirectoryStorage::new(&save_path))
+                .map_err(|error| error.to_string())?;
+            println!("Done");
+        }
+        Command::Check => {
+            let warnings = validate(&load_classes(&cli)?);
+            for warning in &warnings {
+                println!("{}", warning.path);
+                println!("  {}", warning.message);
+                println!("  {}", warning.fix);
+            }
+
+            match warnings.len() {
+                0 => println!("No problems found"),
+                count => return Err(format!("{} problems found", count)),
+            }
+        }
+        Command::Report { output, format } => {
+            let format = format
+                .or_else(|| ReportFormat::from_path(output))
+                .ok_or_else(|| "unknown report format, use --format".to_string())?;
+            let report = Report::new(
+                &load_classes(&cli)?,
+                &format!("ULTRAKILL progress: {}", get_slot_name(&get_save_path(&cli)?)),
+            );
+
+            write(output, report.render(format)).map_err(|error| error.to_string())?;
+            println!("Wrote {} report to {}", format, output.display());
+        }
+        Command::Slots => {
+            for slot in get_slots(&cli) {
+                println!("{:<24}{}", slot.to_string(), slot.path.display());
+            }
+        }
+        Command::Bookmark { command } => match command {
+            BookmarkCommand::Add { path } => {
+                if !path.is_dir() {
+                    return Err(format!("{} is not a directory", path.display()));
+                }
+
+                add_bookmark(path).map_err(|error| error.to_string())?;
+                println!("Bookmarked {}", path.display());
+            }
+            BookmarkCommand::Remove { path } => {
+                remove_bookmark(path).map_err(|error| error.to_string())?;
+                println!("Removed bookmark {}", path.display());
+            }
+        },
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,6 +1,8 @@
-use crate::class::traits::IntoFileInfix;
+use crate::class::traits::{ArrayIndex, IntoFileInfix};
+use bepis_derive::FieldName;
+use serde::{Deserialize, Serialize};
 use std::{num::ParseIntError, str::FromStr};
-use strum::{Display, EnumIter, FromRepr};
+use strum::{Display, EnumIter, FromRepr, IntoEnumIterator};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -20,6 +22,7 @@ pub enum WeaponType {
     #[strum(to_string = "Rocket Launcher")]
     RocketLauncher,
     Arm,
+    Beam,
 }
 
 impl WeaponType {
@@ -30,32 +33,54 @@ impl WeaponType {
                 UnlockableWeaponVariant::MarksmanRevolver,
                 UnlockableWeaponVariant::SharpshooterRevolver,
                 UnlockableWeaponVariant::AlternateRevolver,
+                UnlockableWeaponVariant::YellowRevolver,
             ],
             Self::Shotgun => &[
                 UnlockableWeaponVariant::CoreEjectShotgun,
                 UnlockableWeaponVariant::PumpChargeShotgun,
+                UnlockableWeaponVariant::RedShotgun,
+                UnlockableWeaponVariant::YellowShotgun,
             ],
             Self::Nailgun => &[
                 UnlockableWeaponVariant::AttractorNailgun,
                 UnlockableWeaponVariant::OverheatNailgun,
                 UnlockableWeaponVariant::SawbladeLauncher,
+                UnlockableWeaponVariant::RedNailgun,
+                UnlockableWeaponVariant::YellowNailgun,
             ],
             Self::Railgun => &[
                 UnlockableWeaponVariant::ElectricRailgun,
                 UnlockableWeaponVariant::MaliciousRailgun,
                 UnlockableWeaponVariant::ScrewdriverRailgun,
+                UnlockableWeaponVariant::YellowRailgun,
             ],
             Self::RocketLauncher => &[
                 UnlockableWeaponVariant::FreezeframeRocketLauncher,
                 UnlockableWeaponVariant::SRSCannonRocketLauncher,
+                UnlockableWeaponVariant::FirestarterRocketLauncher,
+                UnlockableWeaponVariant::YellowRocketLauncher,
             ],
             Self::Arm => &[
                 UnlockableWeaponVariant::Knuckleblaster,
                 UnlockableWeaponVariant::Whiplash,
+                UnlockableWeaponVariant::YellowArm,
+            ],
+            Self::Beam => &[
+                UnlockableWeaponVariant::Beam0,
+                UnlockableWeaponVariant::Beam1,
+                UnlockableWeaponVariant::Beam2,
+                UnlockableWeaponVariant::Beam3,
             ],
         }
     }
 
+    /// Weapons the game has save slots for but doesn't give the player yet.
+    pub fn is_unused(&self) -> bool {
+        self.get_unlockable_variants()
+            .iter()
+            .all(|variant| variant.is_unused())
+    }
+
     pub fn get_customizable(&self) -> Option<CustomizableWeaponType> {
         Some(match self {
             Self::Revolver => CustomizableWeaponType::Revolver,
@@ -63,53 +88,197 @@ impl WeaponType {
             Self::Nailgun => CustomizableWeaponType::Nailgun,
             Self::Railgun => CustomizableWeaponType::Railgun,
             Self::RocketLauncher => CustomizableWeaponType::RocketLauncher,
-            Self::Arm => return None,
+            Self::Arm | Self::Beam => return None,
+        })
+    }
+
+    /// The variant that makes up owning the weapon. Every other variant and
+    /// its customization need it first. The arm's feedbacker is always owned.
+    pub fn get_base_variant(&self) -> Option<UnlockableWeaponVariant> {
+        Some(match self {
+            Self::Revolver => UnlockableWeaponVariant::PiercerRevolver,
+            Self::Shotgun => UnlockableWeaponVariant::CoreEjectShotgun,
+            Self::Nailgun => UnlockableWeaponVariant::AttractorNailgun,
+            Self::Railgun => UnlockableWeaponVariant::ElectricRailgun,
+            Self::RocketLauncher => UnlockableWeaponVariant::FreezeframeRocketLauncher,
+            Self::Arm | Self::Beam => return None,
         })
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, EnumIter, Display)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, EnumIter, Display, FieldName)]
 pub enum CustomizableWeaponType {
+    #[bepis(field = "revCustomizationUnlocked")]
     Revolver,
+    #[bepis(field = "shoCustomizationUnlocked")]
     Shotgun,
+    #[bepis(field = "naiCustomizationUnlocked")]
     Nailgun,
+    #[bepis(field = "raiCustomizationUnlocked")]
     Railgun,
     #[strum(to_string = "Rocket Launcher")]
+    #[bepis(field = "rockCustomizationUnlocked")]
     RocketLauncher,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, EnumIter, Display)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, EnumIter, Display, FieldName)]
 pub enum UnlockableWeaponVariant {
     #[strum(to_string = "Piercer Revolver")]
+    #[bepis(field = "rev0")]
     PiercerRevolver,
     #[strum(to_string = "Marksman Revolver")]
+    #[bepis(field = "rev2")]
     MarksmanRevolver,
     #[strum(to_string = "Sharpshooter Revolver")]
+    #[bepis(field = "rev1")]
     SharpshooterRevolver,
     #[strum(to_string = "Alternate Revolver")]
+    #[bepis(field = "revalt")]
     AlternateRevolver,
     #[strum(to_string = "Core Eject Shotgun")]
+    #[bepis(field = "sho0")]
     CoreEjectShotgun,
     #[strum(to_string = "Pump Charge Shotgun")]
+    #[bepis(field = "sho1")]
     PumpChargeShotgun,
     #[strum(to_string = "Attractor Nailgun")]
+    #[bepis(field = "nai0")]
     AttractorNailgun,
     #[strum(to_string = "Overheat Nailgun")]
+    #[bepis(field = "nai1")]
     OverheatNailgun,
     #[strum(to_string = "Sawblade Launcher")]
+    #[bepis(field = "naialt")]
     SawbladeLauncher,
     #[strum(to_string = "Electric Railgun")]
+    #[bepis(field = "rai0")]
     ElectricRailgun,
     #[strum(to_string = "Screwdriver Railgun")]
+    #[bepis(field = "rai2")]
     ScrewdriverRailgun,
     #[strum(to_string = "Malicious Railgun")]
+    #[bepis(field = "rai1")]
     MaliciousRailgun,
     #[strum(to_string = "Freezeframe Rocket Launcher")]
+    #[bepis(field = "rock0")]
     FreezeframeRocketLauncher,
     #[strum(to_string = "S.R.S. Cannon Rocket Launcher")]
+    #[bepis(field = "rock1")]
     SRSCannonRocketLauncher,
+    #[bepis(field = "arm1")]
     Knuckleblaster,
+    #[bepis(field = "arm2")]
     Whiplash,
+    #[strum(to_string = "Yellow Revolver")]
+    #[bepis(field = "rev3")]
+    YellowRevolver,
+    #[strum(to_string = "Red Shotgun")]
+    #[bepis(field = "sho2")]
+    RedShotgun,
+    #[strum(to_string = "Yellow Shotgun")]
+    #[bepis(field = "sho3")]
+    YellowShotgun,
+    #[strum(to_string = "Red Nailgun")]
+    #[bepis(field = "nai2")]
+    RedNailgun,
+    #[strum(to_string = "Yellow Nailgun")]
+    #[bepis(field = "nai3")]
+    YellowNailgun,
+    #[strum(to_string = "Yellow Railgun")]
+    #[bepis(field = "rai3")]
+    YellowRailgun,
+    #[strum(to_string = "Firestarter Rocket Launcher")]
+    #[bepis(field = "rock2")]
+    FirestarterRocketLauncher,
+    #[strum(to_string = "Yellow Rocket Launcher")]
+    #[bepis(field = "rock3")]
+    YellowRocketLauncher,
+    #[strum(to_string = "Yellow Arm")]
+    #[bepis(field = "arm3")]
+    YellowArm,
+    #[strum(to_string = "Beam 0")]
+    #[bepis(field = "beam0")]
+    Beam0,
+    #[strum(to_string = "Beam 1")]
+    #[bepis(field = "beam1")]
+    Beam1,
+    #[strum(to_string = "Beam 2")]
+    #[bepis(field = "beam2")]
+    Beam2,
+    #[strum(to_string = "Beam 3")]
+    #[bepis(field = "beam3")]
+    Beam3,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum WeaponSource {
+    Level(Level),
+    Shop,
+    Found,
+    Unused,
+}
+
+impl std::fmt::Display for WeaponSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Level(level) => write!(f, "Picked up in {}", level),
+            Self::Shop => write!(f, "Bought in the shop"),
+            Self::Found => write!(f, "Found hidden in a level"),
+            Self::Unused => write!(f, "Not in the game yet"),
+        }
+    }
+}
+
+impl UnlockableWeaponVariant {
+    pub fn get_source(&self) -> WeaponSource {
+        if self.is_unused() {
+            return WeaponSource::Unused;
+        }
+
+        if let Some(level) = Level::iter().find(|level| level.get_weapon_unlocks().contains(self)) {
+            return WeaponSource::Level(level);
+        }
+
+        match self {
+            Self::AlternateRevolver | Self::SawbladeLauncher => WeaponSource::Found,
+            _ => WeaponSource::Shop,
+        }
+    }
+
+    /// The price in P of a variant bought in the shop.
+    pub fn get_price(&self) -> Option<u32> {
+        Some(match self {
+            Self::MarksmanRevolver => 7_500,
+            Self::SharpshooterRevolver => 12_500,
+            Self::PumpChargeShotgun => 12_500,
+            Self::OverheatNailgun => 25_000,
+            Self::ScrewdriverRailgun => 100_000,
+            Self::MaliciousRailgun => 100_000,
+            Self::SRSCannonRocketLauncher => 75_000,
+            Self::FirestarterRocketLauncher => 75_000,
+            _ => return None,
+        })
+    }
+
+    /// Slots the game saves but has no weapon for yet. They're kept as they
+    /// are, but left out of completion and presets.
+    pub fn is_unused(&self) -> bool {
+        matches!(
+            self,
+            Self::YellowRevolver
+                | Self::RedShotgun
+                | Self::YellowShotgun
+                | Self::RedNailgun
+                | Self::YellowNailgun
+                | Self::YellowRailgun
+                | Self::YellowRocketLauncher
+                | Self::YellowArm
+                | Self::Beam0
+                | Self::Beam1
+                | Self::Beam2
+                | Self::Beam3
+        )
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, FromRepr, EnumIter, Display)]
@@ -119,6 +288,16 @@ pub enum UnlockableType {
     KITR,
 }
 
+impl ArrayIndex for UnlockableType {
+    fn from_index(index: usize) -> Option<Self> {
+        Self::from_repr(index.try_into().ok()?)
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, FromRepr, EnumIter, Display)]
 #[repr(u8)]
 pub enum EnemyType {
@@ -176,7 +355,91 @@ pub enum EnemyType {
     BigJohnator = 37,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default, FromRepr, Display)]
+impl ArrayIndex for EnemyType {
+    fn from_index(index: usize) -> Option<Self> {
+        Self::from_repr(index.try_into().ok()?)
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl EnemyType {
+    pub fn is_boss(&self) -> bool {
+        matches!(
+            self,
+            Self::V2
+                | Self::Minos
+                | Self::Gabriel
+                | Self::FleshPrison
+                | Self::MinosPrime
+                | Self::V2Second
+                | Self::Leviathan
+                | Self::GabrielSecond
+                | Self::SisyphusPrime
+                | Self::FleshPanopticon
+                | Self::Centaur
+        )
+    }
+
+    /// The level the enemy is first encountered in. Secret enemies aren't part
+    /// of the campaign and have none.
+    pub fn get_first_level(&self) -> Option<Level> {
+        Level::iter().find(|level| level.get_new_enemies().contains(self))
+    }
+
+    /// The identifier sandbox saves use for the enemy, if it can be spawned
+    /// from the sandbox menu.
+    pub fn get_sandbox_id(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::Cerberus => "ultrakill.cerberus",
+            Self::Drone => "ultrakill.drone",
+            Self::HideousMass => "ultrakill.hideous-mass",
+            Self::Filth => "ultrakill.filth",
+            Self::MaliciousFace => "ultrakill.malicious-face",
+            Self::Mindflayer => "ultrakill.mindflayer",
+            Self::Streetcleaner => "ultrakill.streetcleaner",
+            Self::Swordsmachine => "ultrakill.swordsmachine",
+            Self::V2 => "ultrakill.v2",
+            Self::Virtue => "ultrakill.virtue",
+            Self::Stalker => "ultrakill.stalker",
+            Self::Stray => "ultrakill.stray",
+            Self::Schism => "ultrakill.schism",
+            Self::Soldier => "ultrakill.soldier",
+            Self::MinosPrime => "ultrakill.minos-prime",
+            Self::SisypheanInsurrectionist => "ultrakill.insurrectionist",
+            Self::Sentry => "ultrakill.sentry",
+            Self::Idol => "ultrakill.idol",
+            Self::Ferryman => "ultrakill.ferryman",
+            Self::SisyphusPrime => "ultrakill.sisyphus-prime",
+            Self::Mannequin => "ultrakill.mannequin",
+            Self::Gutterman => "ultrakill.gutterman",
+            Self::Guttertank => "ultrakill.guttertank",
+            _ => return None,
+        })
+    }
+
+    pub fn from_sandbox_id(id: &str) -> Option<Self> {
+        Self::iter().find(|enemy| enemy.get_sandbox_id() == Some(id))
+    }
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Copy,
+    Default,
+    FromRepr,
+    EnumIter,
+    Display,
+    Serialize,
+    Deserialize,
+)]
 #[repr(u8)]
 pub enum Lockable {
     #[default]
@@ -185,7 +448,21 @@ pub enum Lockable {
     Completed = 2,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default, FromRepr, Display)]
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Copy,
+    Default,
+    FromRepr,
+    EnumIter,
+    Display,
+    Serialize,
+    Deserialize,
+)]
 #[repr(i8)]
 pub enum LevelRank {
     #[default]
@@ -215,18 +492,21 @@ impl From<i32> for LevelRank {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default, FromRepr)]
-#[repr(u8)]
-pub enum SaveSlot {
-    #[default]
-    One = 1,
-    Two = 2,
-    Three = 3,
-    Four = 4,
-    Five = 5,
-}
-
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default, EnumIter, FromRepr)]
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Copy,
+    Default,
+    EnumIter,
+    FromRepr,
+    Display,
+    Serialize,
+    Deserialize,
+)]
 #[repr(u8)]
 pub enum Difficulty {
     Harmless = 0,
@@ -253,7 +533,8 @@ impl IntoFileInfix for Difficulty {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, EnumIter, Display)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, FromRepr, EnumIter, Display)]
+#[repr(u8)]
 pub enum SecretLevel {
     #[strum(to_string = "0-S: SOMETHING WICKED")]
     SomethingWicked,
@@ -274,6 +555,16 @@ pub enum SecretLevel {
     UnknownPrime3,
 }
 
+impl ArrayIndex for SecretLevel {
+    fn from_index(index: usize) -> Option<Self> {
+        Self::from_repr(index.try_into().ok()?)
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
 impl SecretLevel {
     pub fn is_prime(&self) -> bool {
         match self {
@@ -281,6 +572,11 @@ impl SecretLevel {
             _ => false,
         }
     }
+
+    /// Slots the game keeps for secret missions that aren't in it yet.
+    pub fn is_placeholder(&self) -> bool {
+        matches!(self, Self::UnknownSecret7 | Self::UnknownSecret8)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, FromRepr, EnumIter, Display)]
@@ -333,6 +629,12 @@ pub enum Layer {
 }
 
 impl Layer {
+    pub fn get_act(&self) -> Act {
+        Act::iter()
+            .find(|act| act.get_layers().contains(self))
+            .unwrap_or(Act::Prelude)
+    }
+
     pub fn get_levels(&self) -> &[Level] {
         match self {
             Self::Overture => &[
@@ -403,7 +705,19 @@ impl Layer {
 }
 
 #[derive(
-    Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, FromRepr, EnumIter, Display, Default,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Copy,
+    FromRepr,
+    EnumIter,
+    Display,
+    Default,
+    Serialize,
+    Deserialize,
 )]
 #[repr(u16)]
 pub enum Level {
@@ -490,6 +804,66 @@ impl Level {
         }
     }
 
+    /// Weapons picked up for the first time in this level.
+    pub fn get_weapon_unlocks(&self) -> &[UnlockableWeaponVariant] {
+        match self {
+            Self::IntoTheFire => &[UnlockableWeaponVariant::PiercerRevolver],
+            Self::TheMeatgrinder => &[UnlockableWeaponVariant::CoreEjectShotgun],
+            Self::HeartOfTheSunrise => &[UnlockableWeaponVariant::AttractorNailgun],
+            Self::ClairDeLune => &[UnlockableWeaponVariant::Knuckleblaster],
+            Self::DeathAt20000Volts => &[UnlockableWeaponVariant::ElectricRailgun],
+            Self::BellyOfTheBeast => &[UnlockableWeaponVariant::Whiplash],
+            Self::SlavesToPower => &[UnlockableWeaponVariant::FreezeframeRocketLauncher],
+            _ => &[],
+        }
+    }
+
+    /// Enemies first encountered in this level.
+    pub fn get_new_enemies(&self) -> &[EnemyType] {
+        match self {
+            Self::IntoTheFire => &[EnemyType::Filth, EnemyType::Stray],
+            Self::TheMeatgrinder => &[EnemyType::Schism, EnemyType::Swordsmachine],
+            Self::DoubleDown => &[EnemyType::MaliciousFace],
+            Self::Cerberus => &[EnemyType::Cerberus],
+            Self::HeartOfTheSunrise => &[EnemyType::Drone, EnemyType::Streetcleaner],
+            Self::HallsOfSacredRemains => &[EnemyType::Mindflayer],
+            Self::ClairDeLune => &[EnemyType::V2],
+            Self::Bridgeburner => &[EnemyType::Virtue],
+            Self::CourtOfTheCorpseKing => &[EnemyType::Minos],
+            Self::BellyOfTheBeast => &[EnemyType::HideousMass, EnemyType::Idol],
+            Self::InTheFlesh => &[EnemyType::Gabriel],
+            Self::SlavesToPower => &[EnemyType::Soldier, EnemyType::Stalker],
+            Self::GodDamnTheSun => &[EnemyType::SisypheanInsurrectionist],
+            Self::ClairDeSoleil => &[EnemyType::V2Second],
+            Self::InTheWakeOfPoseidon => &[EnemyType::Ferryman],
+            Self::Leviathan => &[EnemyType::Leviathan],
+            Self::AestheticsOfHate => &[EnemyType::GabrielSecond],
+            Self::GardenOfForkingPaths => &[
+                EnemyType::Sentry,
+                EnemyType::Mannequin,
+                EnemyType::Minotaur,
+            ],
+            Self::LightUpTheNight => &[EnemyType::Gutterman, EnemyType::Guttertank],
+            Self::LikeAntennasToHeaven => &[EnemyType::Centaur],
+            Self::SoulSurvivor => &[EnemyType::FleshPrison, EnemyType::MinosPrime],
+            Self::WaitOfTheWorld => &[EnemyType::FleshPanopticon, EnemyType::SisyphusPrime],
+            _ => &[],
+        }
+    }
+
+    /// The level the campaign continues with after this one, skipping prime
+    /// sanctums.
+    pub fn get_next_story_level(&self) -> Option<Level> {
+        Level::iter()
+            .filter(|level| !level.is_prime())
+            .skip_while(|level| level != self)
+            .nth(1)
+    }
+
+    pub fn get_layer(&self) -> Option<Layer> {
+        Layer::iter().find(|layer| layer.get_levels().contains(self))
+    }
+
     pub fn get_secret_count(&self) -> u8 {
         match self {
             Self::IntoTheFire => 5,
@@ -544,3 +918,92 @@ impl IntoFileInfix for Level {
         Box::new(*self as u16)
     }
 }
+
+/// Where a difficulty is in the campaign: the next level to unlock, or past
+/// the last one once every story level has been played. Presets name it
+/// like a level, or as `"Finished"`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum CampaignPosition {
+    Finished,
+    #[serde(untagged)]
+    Level(Level),
+}
+
+impl std::fmt::Display for CampaignPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Level(level) => write!(f, "{}", level),
+            Self::Finished => write!(f, "CAMPAIGN FINISHED"),
+        }
+    }
+}
+
+impl Default for CampaignPosition {
+    fn default() -> Self {
+        Self::Level(Level::IntoTheFire)
+    }
+}
+
+impl CampaignPosition {
+    fn get_last_level() -> Level {
+        Level::iter()
+            .rev()
+            .find(|level| !level.is_prime())
+            .unwrap_or_default()
+    }
+
+    /// Every position that can be picked, in campaign order.
+    pub fn get_positions() -> Vec<Self> {
+        Level::iter()
+            .filter(|level| !level.is_prime())
+            .map(Self::Level)
+            .chain([Self::Finished])
+            .collect()
+    }
+
+    /// Reads the level number the game stores. Numbers past the last story
+    /// level mean the campaign is finished.
+    pub fn from_level_number(number: i32) -> Self {
+        match u16::try_from(number).ok().and_then(Level::from_repr) {
+            Some(level) if !level.is_prime() => Self::Level(level),
+            _ if number > Self::get_last_level() as i32 => Self::Finished,
+            _ => Self::default(),
+        }
+    }
+
+    /// The game stores a finished campaign as the number after the last story
+    /// level.
+    pub fn get_level_number(&self) -> i32 {
+        match self {
+            Self::Level(level) => *level as i32,
+            Self::Finished => Self::get_last_level() as i32 + 1,
+        }
+    }
+
+    /// The position after playing `level`.
+    pub fn after(level: &Level) -> Self {
+        level
+            .get_next_story_level()
+            .map(Self::Level)
+            .unwrap_or(Self::Finished)
+    }
+
+    /// Whether the game lets you play a story level from the level select.
+    /// Prime sanctums are unlocked by their own state instead.
+    pub fn is_reachable(&self, level: &Level) -> bool {
+        match self {
+            _ if level.is_prime() => false,
+            Self::Level(current_level) => level <= current_level,
+            Self::Finished => true,
+        }
+    }
+
+    /// Whether a story level has been finished to get to this position.
+    pub fn is_past(&self, level: &Level) -> bool {
+        match self {
+            _ if level.is_prime() => false,
+            Self::Level(current_level) => level < current_level,
+            Self::Finished => true,
+        }
+    }
+}
@@ -0,0 +1,16 @@
+pub mod app;
+pub mod archive;
+pub mod bulk;
+pub mod class;
+pub mod enums;
+pub mod loadout;
+pub mod pattern;
+pub mod preset;
+pub mod primes;
+pub mod progression;
+pub mod report;
+pub mod sandbox;
+pub mod saves;
+pub mod shop;
+pub mod stats;
+pub mod validate;
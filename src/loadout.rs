@@ -0,0 +1,73 @@
+use crate::{
+    class::general::GeneralData,
+    enums::{UnlockableWeaponVariant, WeaponType},
+};
+use std::fmt;
+
+/// Something unlocked for a weapon whose base variant isn't, which the game
+/// can't get into on its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub path: String,
+    pub unlocked: String,
+    pub base: UnlockableWeaponVariant,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is unlocked but {} isn't", self.unlocked, self.base)
+    }
+}
+
+pub fn get_conflicts(general: &GeneralData, weapon: &WeaponType) -> Vec<Conflict> {
+    let Some(base) = weapon.get_base_variant() else {
+        return vec![];
+    };
+
+    if general.unlocked_weapons.get(&base) == Some(&true) {
+        return vec![];
+    }
+
+    let mut conflicts = vec![];
+
+    if let Some(customizable) = weapon.get_customizable() {
+        if general.weapons_customizable.get(&customizable) == Some(&true) {
+            conflicts.push(Conflict {
+                path: format!("general.weapons_customizable[{}]", customizable),
+                unlocked: format!("{} customization", customizable),
+                base,
+            });
+        }
+    }
+
+    for variant in weapon.get_unlockable_variants() {
+        if *variant != base
+            && !variant.is_unused()
+            && general.unlocked_weapons.get(variant) == Some(&true)
+        {
+            conflicts.push(Conflict {
+                path: format!("general.unlocked_weapons[{}]", variant),
+                unlocked: variant.to_string(),
+                base,
+            });
+        }
+    }
+
+    conflicts
+}
+
+/// Unlocks every variant of the weapon the game has, along with its
+/// customization.
+pub fn unlock_weapon(general: &mut GeneralData, weapon: &WeaponType) {
+    for variant in weapon.get_unlockable_variants() {
+        if !variant.is_unused() {
+            general.unlocked_weapons.insert(*variant, true);
+        }
+    }
+
+    if let Some(customizable) = weapon.get_customizable() {
+        general.weapons_customizable.insert(customizable, true);
+    }
+
+    general.file_exists = true;
+}
@@ -2,10 +2,7 @@
 
 use std::io::Cursor;
 use eframe::egui::{ViewportBuilder, IconData};
-
-mod app;
-mod class;
-mod enums;
+use ultrakill_save_editor::app;
 
 const WINDOW_NAME: &str = "ULTRAKILL Save Editor";
 const ICON_FILE: &[u8] = include_bytes!("../Icon.ico");
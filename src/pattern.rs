@@ -0,0 +1,284 @@
+use crate::saves::is_valid_file_name;
+use std::{
+    fmt,
+    fs::{read_dir, read_to_string, write},
+    io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use strum::{Display, EnumIter, IntoEnumIterator};
+use thiserror::Error;
+
+pub const PATTERN_SIZE: usize = 16;
+pub const PATTERN_EXTENSION: &str = "cgp";
+
+#[derive(Error, Debug)]
+pub enum PatternParseError {
+    #[error("expected 16 rows of heights and 16 rows of prefabs, found {0} rows")]
+    RowCount(usize),
+    #[error("row {0} has {1} cells instead of 16")]
+    CellCount(usize, usize),
+    #[error("invalid height {1} in row {0}")]
+    InvalidHeight(usize, String),
+    #[error("unknown prefab {1} in row {0}")]
+    UnknownPrefab(usize, char),
+}
+
+#[derive(Error, Debug)]
+pub enum PatternError {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("invalid pattern: {0}")]
+    InvalidPattern(#[from] PatternParseError),
+    #[error("invalid name {0:?}")]
+    InvalidName(String),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, EnumIter, Display)]
+pub enum Prefab {
+    #[default]
+    None,
+    #[strum(to_string = "Melee enemy spawn")]
+    MeleeSpawn,
+    #[strum(to_string = "Projectile enemy spawn")]
+    ProjectileSpawn,
+    #[strum(to_string = "Jump pad")]
+    JumpPad,
+    Stairs,
+    #[strum(to_string = "Hideous Mass spawn")]
+    HideousMass,
+}
+
+impl Prefab {
+    pub fn to_char(&self) -> char {
+        match self {
+            Self::None => '0',
+            Self::MeleeSpawn => 'n',
+            Self::ProjectileSpawn => 'p',
+            Self::JumpPad => 'J',
+            Self::Stairs => 's',
+            Self::HideousMass => 'H',
+        }
+    }
+
+    pub fn from_char(value: char) -> Option<Self> {
+        Self::iter().find(|prefab| prefab.to_char() == value)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Mirror {
+    LeftToRight,
+    TopToBottom,
+}
+
+/// A Cybergrind arena, as the height of every pillar and what is placed on
+/// top of it. Rows run from the top of the file down.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Pattern {
+    pub heights: [[i32; PATTERN_SIZE]; PATTERN_SIZE],
+    pub prefabs: [[Prefab; PATTERN_SIZE]; PATTERN_SIZE],
+}
+
+/// Heights from 0 to 9 are written as one digit, anything else in brackets,
+/// such as `(12)` or `(-3)`.
+fn parse_heights(row: usize, line: &str) -> Result<[i32; PATTERN_SIZE], PatternParseError> {
+    let mut heights = vec![];
+    let mut chars = line.chars();
+
+    while let Some(char) = chars.next() {
+        let text = match char {
+            '(' => chars.by_ref().take_while(|char| *char != ')').collect(),
+            char => char.to_string(),
+        };
+
+        heights.push(
+            text.trim()
+                .parse()
+                .map_err(|_| PatternParseError::InvalidHeight(row, text))?,
+        );
+    }
+
+    let len = heights.len();
+    heights
+        .try_into()
+        .map_err(|_| PatternParseError::CellCount(row, len))
+}
+
+fn parse_prefabs(row: usize, line: &str) -> Result<[Prefab; PATTERN_SIZE], PatternParseError> {
+    let prefabs = line
+        .chars()
+        .map(|char| Prefab::from_char(char).ok_or(PatternParseError::UnknownPrefab(row, char)))
+        .collect::<Result<Vec<Prefab>, PatternParseError>>()?;
+
+    let len = prefabs.len();
+    prefabs
+        .try_into()
+        .map_err(|_| PatternParseError::CellCount(row, len))
+}
+
+impl FromStr for Pattern {
+    type Err = PatternParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        if lines.len() != PATTERN_SIZE * 2 {
+            return Err(PatternParseError::RowCount(lines.len()));
+        }
+
+        let mut pattern = Self::default();
+        for (row, line) in lines[..PATTERN_SIZE].iter().enumerate() {
+            pattern.heights[row] = parse_heights(row + 1, line)?;
+        }
+        for (row, line) in lines[PATTERN_SIZE..].iter().enumerate() {
+            pattern.prefabs[row] = parse_prefabs(PATTERN_SIZE + row + 1, line)?;
+        }
+
+        Ok(pattern)
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.heights {
+            for height in row {
+                match height {
+                    0..=9 => write!(f, "{}", height)?,
+                    height => write!(f, "({})", height)?,
+                }
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f)?;
+
+        for (i, row) in self.prefabs.iter().enumerate() {
+            for prefab in row {
+                write!(f, "{}", prefab.to_char())?;
+            }
+            if i + 1 < PATTERN_SIZE {
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Pattern {
+    pub fn load(path: &Path) -> Result<Self, PatternError> {
+        Ok(read_to_string(path)?.parse()?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), io::Error> {
+        write(path, self.to_string())
+    }
+
+    /// Copies one half of the arena onto the other, flipped, so it is
+    /// symmetrical.
+    pub fn mirror(&mut self, mirror: Mirror) {
+        for row in 0..PATTERN_SIZE {
+            for column in 0..PATTERN_SIZE {
+                let (source, target) = match mirror {
+                    Mirror::LeftToRight if column >= PATTERN_SIZE / 2 => continue,
+                    Mirror::TopToBottom if row >= PATTERN_SIZE / 2 => continue,
+                    Mirror::LeftToRight => ((row, column), (row, PATTERN_SIZE - 1 - column)),
+                    Mirror::TopToBottom => ((row, column), (PATTERN_SIZE - 1 - row, column)),
+                };
+
+                self.heights[target.0][target.1] = self.heights[source.0][source.1];
+                self.prefabs[target.0][target.1] = self.prefabs[source.0][source.1];
+            }
+        }
+    }
+
+    fn count(&self, prefab: Prefab) -> usize {
+        self.prefabs
+            .iter()
+            .flatten()
+            .filter(|value| **value == prefab)
+            .count()
+    }
+
+    /// Problems that make the pattern unplayable, such as having nowhere for
+    /// enemies to spawn.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = vec![];
+
+        for prefab in [Prefab::MeleeSpawn, Prefab::ProjectileSpawn] {
+            if self.count(prefab) == 0 {
+                problems.push(format!("There is no {}", prefab.to_string().to_lowercase()));
+            }
+        }
+
+        for (row, prefabs) in self.prefabs.iter().enumerate() {
+            for (column, prefab) in prefabs.iter().enumerate() {
+                if *prefab != Prefab::Stairs {
+                    continue;
+                }
+
+                let height = self.heights[row][column];
+                let neighbours = [
+                    row.checked_sub(1).map(|row| (row, column)),
+                    Some((row + 1, column)),
+                    column.checked_sub(1).map(|column| (row, column)),
+                    Some((row, column + 1)),
+                ];
+
+                if !neighbours.into_iter().flatten().any(|(row, column)| {
+                    self.heights
+                        .get(row)
+                        .and_then(|heights| heights.get(column))
+                        .is_some_and(|neighbour| *neighbour > height)
+                }) {
+                    problems.push(format!(
+                        "The stairs in row {}, column {} don't lead up to anything",
+                        row + 1,
+                        column + 1
+                    ));
+                }
+            }
+        }
+
+        problems
+    }
+}
+
+/// Where the game keeps custom patterns, next to the saves directory.
+pub fn get_patterns_dir(saves_root: &Path) -> Option<PathBuf> {
+    Some(saves_root.parent()?.join("Cybergrind").join("Patterns"))
+}
+
+/// The path a pattern with the given name is saved to, failing if the name
+/// is unusable.
+pub fn get_pattern_path(dir: &Path, name: &str) -> Result<PathBuf, PatternError> {
+    match is_valid_file_name(name) {
+        true => Ok(dir.join(format!("{}.{}", name, PATTERN_EXTENSION))),
+        false => Err(PatternError::InvalidName(name.to_string())),
+    }
+}
+
+pub fn list_patterns(dir: &Path) -> Result<Vec<PathBuf>, io::Error> {
+    let mut patterns = vec![];
+
+    for entry in read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case(PATTERN_EXTENSION))
+        {
+            patterns.push(path);
+        }
+    }
+
+    patterns.sort();
+    Ok(patterns)
+}
+
+#[cfg(test)]
+mod tests;
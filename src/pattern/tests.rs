@@ -0,0 +1,98 @@
+use super::{get_pattern_path, Mirror, Pattern, PatternParseError, Prefab, PATTERN_SIZE};
+use std::path::Path;
+
+fn sample() -> Pattern {
+    let mut pattern = Pattern::default();
+    pattern.heights[0][0] = 3;
+    pattern.heights[0][1] = 12;
+    pattern.heights[1][2] = -3;
+    pattern.prefabs[0][0] = Prefab::MeleeSpawn;
+    pattern.prefabs[2][3] = Prefab::ProjectileSpawn;
+    pattern.prefabs[15][15] = Prefab::HideousMass;
+
+    pattern
+}
+
+#[test]
+fn round_trip() {
+    let pattern = sample();
+    let text = pattern.to_string();
+
+    assert!(text.starts_with("3(12)00000000000000\n"));
+    assert_eq!(text.lines().nth(1), Some("00(-3)0000000000000"));
+    assert_eq!(text.parse::<Pattern>().unwrap(), pattern);
+}
+
+#[test]
+fn parse_bracketed_heights() {
+    let zeros = "0".repeat(PATTERN_SIZE);
+    // Blank lines, spaces in brackets and Windows line endings are all fine.
+    let text = format!(
+        "(0)( 7 )(-10)(25){}\n{}\n\n{}\r\n",
+        "0".repeat(PATTERN_SIZE - 4),
+        [zeros.as_str(); PATTERN_SIZE - 1].join("\n"),
+        [zeros.as_str(); PATTERN_SIZE].join("\r\n"),
+    );
+
+    let pattern: Pattern = text.parse().unwrap();
+    assert_eq!(pattern.heights[0][..4], [0, 7, -10, 25]);
+    assert_eq!(
+        pattern,
+        Pattern {
+            heights: pattern.heights,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn parse_errors() {
+    let text = sample().to_string();
+
+    assert!(matches!(
+        "".parse::<Pattern>(),
+        Err(PatternParseError::RowCount(0))
+    ));
+    assert!(matches!(
+        text.replacen("3(12)", "3(x)", 1).parse::<Pattern>(),
+        Err(PatternParseError::InvalidHeight(1, _))
+    ));
+    assert!(matches!(
+        text.replacen("3(12)", "3", 1).parse::<Pattern>(),
+        Err(PatternParseError::CellCount(1, 15))
+    ));
+    assert!(matches!(
+        text.replacen('n', "x", 1).parse::<Pattern>(),
+        Err(PatternParseError::UnknownPrefab(17, 'x'))
+    ));
+}
+
+#[test]
+fn mirror() {
+    let mut pattern = sample();
+    pattern.mirror(Mirror::LeftToRight);
+
+    assert_eq!(pattern.heights[0][15], 3);
+    assert_eq!(pattern.heights[0][14], 12);
+    assert_eq!(pattern.prefabs[2][12], Prefab::ProjectileSpawn);
+    // The right half is overwritten by the left.
+    assert_eq!(pattern.prefabs[15][15], Prefab::None);
+
+    pattern.mirror(Mirror::TopToBottom);
+    assert_eq!(pattern.heights[15][15], 3);
+    assert_eq!(pattern.heights[14][2], -3);
+    assert_eq!(pattern.prefabs[13][3], Prefab::ProjectileSpawn);
+}
+
+#[test]
+fn pattern_names() {
+    let dir = Path::new("Patterns");
+
+    assert_eq!(
+        get_pattern_path(dir, "arena").unwrap(),
+        dir.join("arena.cgp")
+    );
+    for name in ["", " ", "..", "a/b", "a\\b", "/arena"] {
+        assert!(get_pattern_path(dir, name).is_err(), "{:?}", name);
+    }
+}
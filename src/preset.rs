@@ -0,0 +1,198 @@
+use crate::{
+    class::classes::Classes,
+    enums::{CampaignPosition, Difficulty, Level, LevelRank, Lockable},
+    saves::get_config_path,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs::read_to_string, io, mem::take, path::PathBuf};
+use strum::IntoEnumIterator;
+use thiserror::Error;
+
+pub const PRESETS_FILE: &str = "presets.json";
+
+#[derive(Error, Debug)]
+pub enum PresetError {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("invalid presets file: {0}")]
+    InvalidFile(#[from] serde_json::Error),
+}
+
+/// A complete set of progress that replaces the campaign progress in a slot.
+/// Cybergrind bests, unused weapon variants and entries from newer game
+/// versions are kept. User presets are read from a JSON list, where every
+/// missing field keeps the value of a fresh save.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preset {
+    pub name: String,
+    pub description: String,
+    /// Difficulties that get progress files, level ranks and prime states.
+    pub difficulties: Vec<Difficulty>,
+    pub rank: LevelRank,
+    pub secrets: bool,
+    pub challenges: bool,
+    pub current_level: Option<CampaignPosition>,
+    pub primes: Lockable,
+    pub weapons: bool,
+    pub bestiary: Lockable,
+    pub secret_missions: Lockable,
+    pub limbo_switches: bool,
+    pub intro_seen: bool,
+    pub tutorial_beat: bool,
+    pub money: Option<u32>,
+}
+
+impl Preset {
+    pub fn apply(&self, classes: &mut Classes) {
+        let loaded = take(classes);
+        classes.cybergrind = loaded.cybergrind;
+
+        let general = &mut classes.general;
+        general.unknown_secret_missions = loaded.general.unknown_secret_missions;
+        general.unknown_enemies = loaded.general.unknown_enemies;
+        general.unknown_unlockables = loaded.general.unknown_unlockables;
+        for (variant, unlocked) in loaded.general.unlocked_weapons {
+            if variant.is_unused() {
+                general.unlocked_weapons.insert(variant, unlocked);
+            }
+        }
+
+        general.intro_seen = self.intro_seen;
+        general.tutorial_beat = self.tutorial_beat;
+        general
+            .unlocked_weapons
+            .iter_mut()
+            .filter(|(variant, _)| !variant.is_unused())
+            .for_each(|(_, unlocked)| *unlocked = self.weapons);
+        general
+            .weapons_customizable
+            .values_mut()
+            .for_each(|customizable| *customizable = self.weapons);
+        general
+            .enemies_discovered
+            .values_mut()
+            .for_each(|state| *state = self.bestiary);
+        for (secret_level, state) in general.secret_missions.iter_mut() {
+            *state = match secret_level.is_prime() {
+                true => self.primes,
+                false => self.secret_missions,
+            };
+        }
+        general.limbo_switches.fill(self.limbo_switches);
+        if let Some(money) = self.money {
+            general.money = money.to_string();
+        }
+        let kept = !general.unknown_secret_missions.is_empty()
+            || !general.unknown_enemies.is_empty()
+            || !general.unknown_unlockables.is_empty()
+            || general
+                .unlocked_weapons
+                .iter()
+                .any(|(variant, unlocked)| variant.is_unused() && *unlocked);
+        general.file_exists = kept
+            || self.intro_seen
+            || self.tutorial_beat
+            || self.weapons
+            || self.bestiary != Lockable::Locked
+            || self.secret_missions != Lockable::Locked
+            || self.primes != Lockable::Locked
+            || self.limbo_switches
+            || self.money.is_some();
+
+        for difficulty in &self.difficulties {
+            if let Some(difficulty_data) = classes.difficulty.get_mut(difficulty) {
+                difficulty_data.file_exists = true;
+                difficulty_data.prime_levels.fill(self.primes);
+                if let Some(current_level) = self.current_level {
+                    difficulty_data.current_level = current_level;
+                }
+            }
+        }
+
+        if self.rank != LevelRank::None || self.secrets || self.challenges {
+            for (level, level_data) in classes.levels.iter_mut() {
+                level_data.file_exists = true;
+                level_data.secrets_found.fill(self.secrets);
+                level_data.challenge = self.challenges && !level.is_prime();
+
+                for difficulty in &self.difficulties {
+                    if let Some(rank) = level_data.ranks.get_mut(*difficulty as usize) {
+                        *rank = self.rank;
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn get_builtin_presets() -> Vec<Preset> {
+    vec![
+        Preset {
+            name: "Fresh".to_string(),
+            description: "An empty slot, as if the game was never started".to_string(),
+            ..Default::default()
+        },
+        Preset {
+            name: "100%".to_string(),
+            description: "P-rank, every secret and challenge, primes, weapons and bestiary on \
+                          every difficulty"
+                .to_string(),
+            difficulties: Difficulty::iter().collect(),
+            rank: LevelRank::P,
+            secrets: true,
+            challenges: true,
+            current_level: Some(CampaignPosition::Finished),
+            primes: Lockable::Completed,
+            weapons: true,
+            bestiary: Lockable::Completed,
+            secret_missions: Lockable::Completed,
+            limbo_switches: true,
+            intro_seen: true,
+            tutorial_beat: true,
+            money: None,
+        },
+        Preset {
+            name: "Any%-ready".to_string(),
+            description: "Intro and tutorial done, every difficulty starting at 0-1".to_string(),
+            difficulties: Difficulty::iter().collect(),
+            current_level: Some(CampaignPosition::Level(Level::IntoTheFire)),
+            intro_seen: true,
+            tutorial_beat: true,
+            ..Default::default()
+        },
+        Preset {
+            name: "All weapons".to_string(),
+            description: "Every weapon variant unlocked and customizable, nothing else".to_string(),
+            weapons: true,
+            intro_seen: true,
+            tutorial_beat: true,
+            ..Default::default()
+        },
+    ]
+}
+
+pub fn get_presets_path() -> Option<PathBuf> {
+    get_config_path(PRESETS_FILE)
+}
+
+/// Reads the user's presets, if they have a presets file.
+pub fn load_user_presets() -> Result<Vec<Preset>, PresetError> {
+    let Some(path) = get_presets_path() else {
+        return Ok(vec![]);
+    };
+
+    match read_to_string(path) {
+        Ok(presets) => Ok(serde_json::from_str(&presets)?),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Built-in presets followed by the user's own.
+pub fn load_presets() -> Result<Vec<Preset>, PresetError> {
+    let mut presets = get_builtin_presets();
+    presets.extend(load_user_presets()?);
+
+    Ok(presets)
+}
@@ -0,0 +1,167 @@
+use crate::{
+    class::classes::Classes,
+    enums::{Difficulty, Level, LevelRank, Lockable, SecretLevel},
+};
+use strum::IntoEnumIterator;
+
+/// A prime sanctum, whose progress is split between its state on every
+/// difficulty, its secret mission and the ranks of its level. Not every prime
+/// has a level yet.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Prime {
+    pub index: usize,
+    pub secret_level: SecretLevel,
+    pub level: Option<Level>,
+}
+
+impl Prime {
+    pub fn get_primes() -> Vec<Self> {
+        SecretLevel::iter()
+            .filter(|secret_level| secret_level.is_prime())
+            .enumerate()
+            .map(|(index, secret_level)| Self {
+                index,
+                secret_level,
+                level: Level::iter().find(|level| level.get_prime_index() == Some(index as u8)),
+            })
+            .collect()
+    }
+
+    pub fn from_level(level: &Level) -> Option<Self> {
+        Self::get_primes()
+            .into_iter()
+            .find(|prime| prime.level == Some(*level))
+    }
+
+    pub fn from_secret_level(secret_level: &SecretLevel) -> Option<Self> {
+        Self::get_primes()
+            .into_iter()
+            .find(|prime| prime.secret_level == *secret_level)
+    }
+
+    pub fn get_name(&self) -> String {
+        match self.level {
+            Some(level) => level.to_string(),
+            None => format!("P-{}", self.index + 1),
+        }
+    }
+
+    pub fn get_state(&self, classes: &Classes, difficulty: Difficulty) -> Option<Lockable> {
+        classes
+            .difficulty
+            .get(&difficulty)?
+            .prime_levels
+            .get(self.index)
+            .copied()
+    }
+
+    pub fn get_rank(&self, classes: &Classes, difficulty: Difficulty) -> Option<LevelRank> {
+        classes
+            .levels
+            .get(&self.level?)?
+            .ranks
+            .get(difficulty as usize)
+            .copied()
+    }
+
+    pub fn get_secret_mission(&self, classes: &Classes) -> Lockable {
+        classes
+            .general
+            .secret_missions
+            .get(&self.secret_level)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Sets the state on one difficulty. The secret mission follows the best
+    /// state of any difficulty.
+    pub fn set_state(&self, classes: &mut Classes, difficulty: Difficulty, state: Lockable) {
+        if let Some(difficulty_data) = classes.difficulty.get_mut(&difficulty) {
+            if let Some(value) = difficulty_data.prime_levels.get_mut(self.index) {
+                *value = state;
+                difficulty_data.file_exists = true;
+            }
+        }
+
+        let best = Difficulty::iter()
+            .filter_map(|difficulty| self.get_state(classes, difficulty))
+            .max()
+            .unwrap_or_default();
+        classes
+            .general
+            .secret_missions
+            .insert(self.secret_level, best);
+        classes.general.file_exists = true;
+    }
+
+    /// Sets the rank on one difficulty. Any rank means the prime was beaten,
+    /// so it is also completed on that difficulty.
+    pub fn set_rank(&self, classes: &mut Classes, difficulty: Difficulty, rank: LevelRank) {
+        let Some(level_data) = self.level.and_then(|level| classes.levels.get_mut(&level)) else {
+            return;
+        };
+
+        if let Some(value) = level_data.ranks.get_mut(difficulty as usize) {
+            *value = rank;
+            level_data.file_exists |= rank != LevelRank::None;
+        }
+
+        if rank != LevelRank::None {
+            self.set_state(classes, difficulty, Lockable::Completed);
+        }
+    }
+
+    /// Sets the secret mission, which is the best state of any difficulty.
+    /// Difficulties past it are lowered to it, and if none reach it,
+    /// `difficulty` is raised to it.
+    pub fn set_secret_mission(
+        &self,
+        classes: &mut Classes,
+        difficulty: Difficulty,
+        state: Lockable,
+    ) {
+        classes
+            .general
+            .secret_missions
+            .insert(self.secret_level, state);
+        classes.general.file_exists = true;
+
+        for difficulty_data in classes.difficulty.values_mut() {
+            if let Some(value) = difficulty_data.prime_levels.get_mut(self.index) {
+                if *value > state {
+                    *value = state;
+                    difficulty_data.file_exists = true;
+                }
+            }
+        }
+
+        let reached = Difficulty::iter()
+            .filter_map(|difficulty| self.get_state(classes, difficulty))
+            .any(|value| value == state);
+
+        if !reached {
+            self.set_state(classes, difficulty, state);
+        }
+    }
+}
+
+/// Sets a level's rank on one difficulty. Prime sanctums go through
+/// `Prime::set_rank`, so their state and secret mission follow.
+pub fn set_level_rank(
+    classes: &mut Classes,
+    level: Level,
+    difficulty: Difficulty,
+    rank: LevelRank,
+) {
+    if let Some(prime) = Prime::from_level(&level) {
+        prime.set_rank(classes, difficulty, rank);
+        return;
+    }
+
+    if let Some(level_data) = classes.levels.get_mut(&level) {
+        if let Some(value) = level_data.ranks.get_mut(difficulty as usize) {
+            *value = rank;
+            level_data.file_exists |= rank != LevelRank::None;
+        }
+    }
+}
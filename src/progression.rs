@@ -0,0 +1,173 @@
+use crate::{
+    class::classes::Classes,
+    enums::{Act, CampaignPosition, Difficulty, Level, LevelRank, Lockable, SecretLevel},
+};
+use std::fmt;
+use strum::IntoEnumIterator;
+
+/// Points given for each level played, so the total looks like a real
+/// playthrough that bought nothing.
+pub const MONEY_PER_LEVEL: u32 = 20_000;
+
+fn get_act(level: &Level) -> Option<Act> {
+    Some(level.get_layer()?.get_act())
+}
+
+fn get_story_levels(act: &Act) -> Vec<Level> {
+    act.get_layers()
+        .iter()
+        .flat_map(|layer| layer.get_levels().iter().copied())
+        .filter(|level| !level.is_prime())
+        .collect()
+}
+
+/// Progress of someone who has played every level up to `level` in order on
+/// `difficulty`, finishing each with `rank`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progression {
+    pub level: Level,
+    pub difficulty: Difficulty,
+    pub rank: LevelRank,
+}
+
+impl Progression {
+    /// Every level that has been finished, in the order they are played. A
+    /// prime sanctum is played after the rest of its act.
+    pub fn get_played_levels(&self) -> Vec<Level> {
+        let last = match self.level.is_prime() {
+            true => get_act(&self.level)
+                .and_then(|act| get_story_levels(&act).last().copied())
+                .unwrap_or(self.level),
+            false => self.level,
+        };
+
+        let mut levels = vec![];
+        for level in Level::iter().filter(|level| !level.is_prime()) {
+            levels.push(level);
+            if level == last {
+                break;
+            }
+        }
+
+        if self.level.is_prime() {
+            levels.push(self.level);
+        }
+
+        levels
+    }
+
+    /// Levels after the target lose their rank on the difficulty, and their
+    /// file if nothing else is left in it. Money is only set for a save that
+    /// has no general progress yet, so existing money is kept.
+    pub fn apply(&self, classes: &mut Classes) {
+        let played = self.get_played_levels();
+        let index = self.difficulty as usize;
+
+        for (level, level_data) in &mut classes.levels {
+            if played.contains(level) {
+                level_data.file_exists = true;
+                if let Some(rank) = level_data.ranks.get_mut(index) {
+                    *rank = self.rank;
+                }
+            } else {
+                if let Some(rank) = level_data.ranks.get_mut(index) {
+                    *rank = LevelRank::None;
+                }
+
+                let empty = level_data.ranks.iter().all(|rank| *rank == LevelRank::None)
+                    && !level_data.secrets_found.contains(&true)
+                    && !level_data.major_assists.contains(&true)
+                    && !level_data.challenge;
+                level_data.file_exists &= !empty;
+            }
+        }
+
+        let general = &mut classes.general;
+        if !general.file_exists {
+            general.money = (played.len() as u32 * MONEY_PER_LEVEL).to_string();
+        }
+        general.file_exists = true;
+        general.intro_seen = true;
+        general.tutorial_beat = true;
+
+        for level in &played {
+            for variant in level.get_weapon_unlocks() {
+                general.unlocked_weapons.insert(*variant, true);
+            }
+
+            // Met, but not necessarily fully discovered, which needs more
+            // than finishing the level.
+            for enemy in level.get_new_enemies() {
+                let state = general.enemies_discovered.entry(*enemy).or_default();
+                *state = (*state).max(Lockable::Unlocked);
+            }
+
+            if let Some(layer) = level.get_layer() {
+                let secret_level = layer.get_secret_level();
+                let layer_played = layer
+                    .get_levels()
+                    .iter()
+                    .filter(|level| !level.is_prime())
+                    .all(|level| played.contains(level));
+
+                if layer_played && !secret_level.is_prime() {
+                    let state = general.secret_missions.entry(secret_level).or_default();
+                    *state = (*state).max(Lockable::Unlocked);
+                }
+            }
+        }
+
+        if general.secret_missions.get(&SecretLevel::TheWitless) != Some(&Lockable::Locked) {
+            general.limbo_switches.fill(true);
+        }
+
+        let Some(difficulty_data) = classes.difficulty.get_mut(&self.difficulty) else {
+            return;
+        };
+
+        difficulty_data.file_exists = true;
+
+        for act in Act::iter() {
+            let story_played = get_story_levels(&act)
+                .iter()
+                .all(|level| played.contains(level));
+
+            for layer in act.get_layers() {
+                for prime in layer.get_levels().iter().filter(|level| level.is_prime()) {
+                    let state = match played.contains(prime) {
+                        true => Lockable::Completed,
+                        false if story_played && self.rank == LevelRank::P => Lockable::Unlocked,
+                        false => Lockable::Locked,
+                    };
+
+                    if let Some(value) = prime
+                        .get_prime_index()
+                        .and_then(|index| difficulty_data.prime_levels.get_mut(index as usize))
+                    {
+                        *value = state;
+                    }
+
+                    let mission = general
+                        .secret_missions
+                        .entry(layer.get_secret_level())
+                        .or_default();
+                    *mission = (*mission).max(state);
+                }
+            }
+        }
+
+        if let Some(last) = played.iter().rev().find(|level| !level.is_prime()) {
+            difficulty_data.current_level = CampaignPosition::after(last);
+        }
+    }
+}
+
+impl fmt::Display for Progression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Play through {} on {} with {} ranks",
+            self.level, self.difficulty, self.rank
+        )
+    }
+}
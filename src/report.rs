@@ -0,0 +1,327 @@
+use crate::{
+    class::{classes::Classes, cybergrind::format_time},
+    enums::{Difficulty, EnemyType, Level, LevelRank, SecretLevel},
+    stats::{Stats, COMPLETION_COLUMNS},
+};
+use std::{fmt::Write, path::Path, str::FromStr};
+use strum::{Display, EnumIter, IntoEnumIterator};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("unknown report format {0}, expected md, html or csv")]
+pub struct ReportFormatError(String);
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, EnumIter, Display)]
+pub enum ReportFormat {
+    Markdown,
+    #[strum(to_string = "HTML")]
+    Html,
+    #[strum(to_string = "CSV")]
+    Csv,
+}
+
+impl ReportFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = ReportFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "md" | "markdown" => Ok(Self::Markdown),
+            "html" | "htm" => Ok(Self::Html),
+            "csv" => Ok(Self::Csv),
+            _ => Err(ReportFormatError(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Section {
+    pub title: String,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Section {
+    fn new<T: ToString>(title: &str, headers: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            title: title.to_string(),
+            headers: headers.into_iter().map(|header| header.to_string()).collect(),
+            rows: vec![],
+        }
+    }
+}
+
+/// Progress of a slot laid out as tables, without any of the save data
+/// itself.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub title: String,
+    pub sections: Vec<Section>,
+}
+
+fn difficulty_headers(first: &str) -> impl Iterator<Item = String> {
+    [first.to_string()]
+        .into_iter()
+        .chain(Difficulty::iter().map(|difficulty| difficulty.to_string()))
+}
+
+fn format_rank(rank: Option<&LevelRank>) -> String {
+    match rank {
+        None | Some(LevelRank::None) => "-".to_string(),
+        Some(rank) => rank.to_string(),
+    }
+}
+
+impl Report {
+    pub fn new(classes: &Classes, title: &str) -> Self {
+        let mut sections = vec![];
+
+        let stats = Stats::new(classes);
+        let mut summary = Section::new("Summary", ["Metric", "Progress"]);
+        for (label, progress) in COMPLETION_COLUMNS
+            .into_iter()
+            .zip(stats.overall.columns())
+            .chain(stats.totals())
+        {
+            summary.rows.push(vec![label.to_string(), progress.to_string()]);
+        }
+        sections.push(summary);
+
+        let mut ranks = Section::new(
+            "Levels",
+            difficulty_headers("Level").chain(["Secrets".to_string(), "Challenge".to_string()]),
+        );
+        for level in Level::iter() {
+            let data = classes.levels.get(&level).filter(|data| data.file_exists);
+            let mut row = vec![level.to_string()];
+
+            row.extend(Difficulty::iter().map(|difficulty| {
+                format_rank(data.and_then(|data| data.ranks.get(difficulty as usize)))
+            }));
+            row.push(match level.get_secret_count() {
+                0 => "-".to_string(),
+                total => format!(
+                    "{}/{}",
+                    data.map(|data| data.secrets_found.iter().filter(|found| **found).count())
+                        .unwrap_or_default(),
+                    total
+                ),
+            });
+            row.push(match (level.is_prime(), data.is_some_and(|data| data.challenge)) {
+                (true, _) => "-".to_string(),
+                (false, true) => "Done".to_string(),
+                (false, false) => "".to_string(),
+            });
+
+            ranks.rows.push(row);
+        }
+        sections.push(ranks);
+
+        let mut primes = Section::new("Prime sanctums", difficulty_headers("Prime sanctum"));
+        for level in Level::iter() {
+            let Some(prime_index) = level.get_prime_index() else {
+                continue;
+            };
+
+            let mut row = vec![level.to_string()];
+            row.extend(Difficulty::iter().map(|difficulty| {
+                classes
+                    .difficulty
+                    .get(&difficulty)
+                    .filter(|data| data.file_exists)
+                    .and_then(|data| data.prime_levels.get(prime_index as usize))
+                    .map(|lockable| lockable.to_string())
+                    .unwrap_or_else(|| "-".to_string())
+            }));
+            primes.rows.push(row);
+        }
+        sections.push(primes);
+
+        let general = classes.general.file_exists.then_some(&classes.general);
+
+        let mut secret_missions = Section::new("Secret missions", ["Secret mission", "Status"]);
+        for secret_level in
+            SecretLevel::iter().filter(|level| !level.is_prime() && !level.is_placeholder())
+        {
+            secret_missions.rows.push(vec![
+                secret_level.to_string(),
+                general
+                    .and_then(|general| general.secret_missions.get(&secret_level))
+                    .map(|lockable| lockable.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            ]);
+        }
+        sections.push(secret_missions);
+
+        let mut bestiary = Section::new("Bestiary", ["Enemy", "Status"]);
+        for enemy in EnemyType::iter() {
+            bestiary.rows.push(vec![
+                enemy.to_string(),
+                general
+                    .and_then(|general| general.enemies_discovered.get(&enemy))
+                    .map(|lockable| lockable.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            ]);
+        }
+        for (index, lockable) in general
+            .into_iter()
+            .flat_map(|general| &general.unknown_enemies)
+        {
+            bestiary.rows.push(vec![
+                format!("Unknown enemy ({})", index),
+                lockable.to_string(),
+            ]);
+        }
+        sections.push(bestiary);
+
+        let mut cybergrind = Section::new(
+            "Cybergrind",
+            ["Difficulty", "Wave", "Kills", "Style", "Time"],
+        );
+        if classes.cybergrind.file_exists {
+            for difficulty in Difficulty::iter() {
+                let index = difficulty as usize;
+                let get = |values: &Vec<String>| values.get(index).cloned().unwrap_or_default();
+
+                cybergrind.rows.push(vec![
+                    difficulty.to_string(),
+                    get(&classes.cybergrind.waves),
+                    get(&classes.cybergrind.kills),
+                    get(&classes.cybergrind.style),
+                    format_time(get(&classes.cybergrind.times).parse().unwrap_or_default()),
+                ]);
+            }
+        }
+        sections.push(cybergrind);
+
+        Self {
+            title: title.to_string(),
+            sections,
+        }
+    }
+
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.render_markdown(),
+            ReportFormat::Html => self.render_html(),
+            ReportFormat::Csv => self.render_csv(),
+        }
+    }
+
+    fn render_markdown(&self) -> String {
+        let cell = |value: &str| value.replace('|', "\\|");
+        let row = |values: &[String]| {
+            format!(
+                "| {} |\n",
+                values
+                    .iter()
+                    .map(|value| cell(value))
+                    .collect::<Vec<String>>()
+                    .join(" | ")
+            )
+        };
+
+        let mut output = format!("# {}\n", self.title);
+
+        for section in &self.sections {
+            write!(output, "\n## {}\n\n", section.title).ok();
+
+            if section.rows.is_empty() {
+                output.push_str("No data.\n");
+                continue;
+            }
+
+            output.push_str(&row(&section.headers));
+            output.push_str(&row(&vec!["---".to_string(); section.headers.len()]));
+            for values in &section.rows {
+                output.push_str(&row(values));
+            }
+        }
+
+        output
+    }
+
+    fn render_html(&self) -> String {
+        let mut output = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n\
+             <style>\nbody {{ font-family: sans-serif; }}\n\
+             table {{ border-collapse: collapse; margin-bottom: 1em; }}\n\
+             th, td {{ border: 1px solid #888; padding: 2px 8px; }}\n</style>\n\
+             </head>\n<body>\n<h1>{0}</h1>\n",
+            escape_html(&self.title)
+        );
+
+        for section in &self.sections {
+            writeln!(output, "<h2>{}</h2>", escape_html(&section.title)).ok();
+
+            if section.rows.is_empty() {
+                output.push_str("<p>No data.</p>\n");
+                continue;
+            }
+
+            output.push_str("<table>\n<tr>");
+            for header in &section.headers {
+                write!(output, "<th>{}</th>", escape_html(header)).ok();
+            }
+            output.push_str("</tr>\n");
+
+            for values in &section.rows {
+                output.push_str("<tr>");
+                for value in values {
+                    write!(output, "<td>{}</td>", escape_html(value)).ok();
+                }
+                output.push_str("</tr>\n");
+            }
+            output.push_str("</table>\n");
+        }
+
+        output.push_str("</body>\n</html>\n");
+        output
+    }
+
+    /// Tables are written one after another, each with its own header row, so
+    /// the number of columns changes between sections. Every row starts with
+    /// its section's title, so the rows of one table can be filtered out.
+    fn render_csv(&self) -> String {
+        let mut output = String::new();
+
+        for section in &self.sections {
+            let mut header = vec!["Section".to_string()];
+            header.extend(section.headers.iter().cloned());
+            output.push_str(&csv_row(&header));
+
+            for values in &section.rows {
+                let mut row = vec![section.title.clone()];
+                row.extend(values.iter().cloned());
+                output.push_str(&csv_row(&row));
+            }
+        }
+
+        output
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn csv_row(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|value| match value.contains([',', '"', '\n']) {
+            true => format!("\"{}\"", value.replace('"', "\"\"")),
+            false => value.clone(),
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+        + "\n"
+}
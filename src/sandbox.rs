@@ -0,0 +1,226 @@
+use crate::{enums::EnemyType, saves::is_valid_file_name};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    fs::{copy, read_dir, read_to_string, remove_file, rename, write},
+    io,
+    path::{Path, PathBuf},
+};
+use strum::{Display, EnumIter};
+use thiserror::Error;
+
+pub const SANDBOX_EXTENSION: &str = "pitr";
+
+#[derive(Error, Debug)]
+pub enum SandboxError {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("invalid sandbox save: {0}")]
+    InvalidSave(#[from] serde_json::Error),
+    #[error("invalid name {0:?}")]
+    InvalidName(String),
+    #[error("a sandbox save named {0} already exists")]
+    AlreadyExists(String),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Vector {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Quaternion {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            w: 1.0,
+        }
+    }
+}
+
+impl Quaternion {
+    /// Builds a rotation from Euler angles in degrees, applied in the same
+    /// Z, X, Y order as Unity.
+    pub fn from_euler(angles: Vector) -> Self {
+        let (sx, cx) = (angles.x.to_radians() / 2.0).sin_cos();
+        let (sy, cy) = (angles.y.to_radians() / 2.0).sin_cos();
+        let (sz, cz) = (angles.z.to_radians() / 2.0).sin_cos();
+
+        Self {
+            x: cy * sx * cz + sy * cx * sz,
+            y: sy * cx * cz - cy * sx * sz,
+            z: cy * cx * sz - sy * sx * cz,
+            w: cy * cx * cz + sy * sx * sz,
+        }
+    }
+
+    /// The Euler angles in degrees, as shown in Unity's inspector.
+    pub fn to_euler(&self) -> Vector {
+        let Self { x, y, z, w } = *self;
+        let sin_x = (2.0 * (w * x - y * z)).clamp(-1.0, 1.0);
+
+        let (pitch, yaw, roll) = match sin_x.abs() < 0.9999 {
+            true => (
+                sin_x.asin(),
+                (2.0 * (x * z + w * y)).atan2(1.0 - 2.0 * (x * x + y * y)),
+                (2.0 * (x * y + w * z)).atan2(1.0 - 2.0 * (x * x + z * z)),
+            ),
+            // Looking straight up or down, where yaw and roll turn the same way.
+            false => (
+                sin_x.asin(),
+                (-2.0 * (x * z - w * y)).atan2(1.0 - 2.0 * (y * y + z * z)),
+                0.0,
+            ),
+        };
+
+        Vector {
+            x: pitch.to_degrees().rem_euclid(360.0),
+            y: yaw.to_degrees().rem_euclid(360.0),
+            z: roll.to_degrees().rem_euclid(360.0),
+        }
+    }
+}
+
+/// A block, prop or enemy placed in the sandbox. Anything the editor doesn't
+/// touch, such as physics settings and per-object data, is kept as it was.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SandboxObject {
+    pub object_identifier: String,
+    pub position: Vector,
+    pub rotation: Quaternion,
+    pub scale: Vector,
+    /// Only blocks have a size, which they use instead of their scale.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_size: Option<Vector>,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl SandboxObject {
+    pub fn get_enemy_type(&self) -> Option<EnemyType> {
+        EnemyType::from_sandbox_id(&self.object_identifier)
+    }
+
+    /// Returns false if the enemy can't be spawned in the sandbox.
+    pub fn set_enemy_type(&mut self, enemy: EnemyType) -> bool {
+        match enemy.get_sandbox_id() {
+            Some(id) => {
+                self.object_identifier = id.to_string();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// The lists a sandbox save keeps its objects in.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, EnumIter, Display)]
+pub enum SandboxCategory {
+    Blocks,
+    Props,
+    Enemies,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SandboxSave {
+    #[serde(default)]
+    pub blocks: Vec<SandboxObject>,
+    #[serde(default)]
+    pub props: Vec<SandboxObject>,
+    #[serde(default)]
+    pub enemies: Vec<SandboxObject>,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl SandboxSave {
+    pub fn load(path: &Path) -> Result<Self, SandboxError> {
+        Ok(serde_json::from_str(&read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SandboxError> {
+        Ok(write(path, serde_json::to_string(self)?)?)
+    }
+
+    pub fn get_objects_mut(&mut self, category: SandboxCategory) -> &mut Vec<SandboxObject> {
+        match category {
+            SandboxCategory::Blocks => &mut self.blocks,
+            SandboxCategory::Props => &mut self.props,
+            SandboxCategory::Enemies => &mut self.enemies,
+        }
+    }
+}
+
+/// Where the game keeps sandbox saves, next to the slots.
+pub fn get_sandbox_dir(saves_root: &Path) -> PathBuf {
+    saves_root.join("Sandbox")
+}
+
+pub fn list_sandbox_saves(dir: &Path) -> Result<Vec<PathBuf>, io::Error> {
+    let mut saves = vec![];
+
+    for entry in read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case(SANDBOX_EXTENSION))
+        {
+            saves.push(path);
+        }
+    }
+
+    saves.sort();
+    Ok(saves)
+}
+
+pub fn get_save_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// The path a save with the given name would have in the same directory,
+/// failing if the name is unusable or already taken.
+fn get_new_path(path: &Path, name: &str) -> Result<PathBuf, SandboxError> {
+    if !is_valid_file_name(name) {
+        return Err(SandboxError::InvalidName(name.to_string()));
+    }
+
+    let new_path = path.with_file_name(format!("{}.{}", name, SANDBOX_EXTENSION));
+    match new_path.try_exists()? {
+        true => Err(SandboxError::AlreadyExists(name.to_string())),
+        false => Ok(new_path),
+    }
+}
+
+pub fn rename_save(path: &Path, name: &str) -> Result<PathBuf, SandboxError> {
+    let new_path = get_new_path(path, name)?;
+    rename(path, &new_path)?;
+    Ok(new_path)
+}
+
+pub fn duplicate_save(path: &Path, name: &str) -> Result<PathBuf, SandboxError> {
+    let new_path = get_new_path(path, name)?;
+    copy(path, &new_path)?;
+    Ok(new_path)
+}
+
+pub fn delete_save(path: &Path) -> Result<(), SandboxError> {
+    Ok(remove_file(path)?)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,141 @@
+use super::{duplicate_save, rename_save, Quaternion, SandboxError, SandboxSave, Vector};
+use crate::enums::EnemyType;
+use serde_json::{json, Value};
+use std::{
+    env::temp_dir,
+    fs::{create_dir_all, remove_dir_all, write},
+    process::id,
+};
+
+fn assert_close(actual: Vector, expected: Vector) {
+    for (actual, expected) in [
+        (actual.x, expected.x),
+        (actual.y, expected.y),
+        (actual.z, expected.z),
+    ] {
+        let difference = (actual - expected).rem_euclid(360.0);
+        assert!(
+            difference.min(360.0 - difference) < 0.01,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+}
+
+#[test]
+fn euler_round_trip() {
+    for angles in [
+        Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        },
+        Vector {
+            x: 30.0,
+            y: 45.0,
+            z: 60.0,
+        },
+        Vector {
+            x: 350.0,
+            y: 180.0,
+            z: 10.0,
+        },
+    ] {
+        assert_close(Quaternion::from_euler(angles).to_euler(), angles);
+    }
+}
+
+#[test]
+fn euler_round_trip_looking_down() {
+    // Yaw and roll turn the same way here, so only the rotation has to match.
+    let rotation = Quaternion::from_euler(Vector {
+        x: 270.0,
+        y: 90.0,
+        z: 30.0,
+    });
+    let Quaternion { x, y, z, w } = Quaternion::from_euler(rotation.to_euler());
+    let dot = x * rotation.x + y * rotation.y + z * rotation.z + w * rotation.w;
+
+    assert!(dot.abs() > 0.9999, "{:?}", rotation.to_euler());
+}
+
+#[test]
+fn euler_matches_unity() {
+    // A quarter turn around Y, as Unity stores it.
+    let rotation = Quaternion::from_euler(Vector {
+        x: 0.0,
+        y: 90.0,
+        z: 0.0,
+    });
+    let half = 0.5f32.sqrt();
+
+    assert!((rotation.y - half).abs() < 1e-6);
+    assert!((rotation.w - half).abs() < 1e-6);
+    assert_eq!(Quaternion::default().to_euler(), Vector::default());
+}
+
+#[test]
+fn keeps_unknown_fields() {
+    let json = json!({
+        "MapName": "arena",
+        "Blocks": [{
+            "ObjectIdentifier": "ultrakill.brush-block",
+            "Position": { "x": 1.0, "y": 2.0, "z": 3.0 },
+            "Rotation": { "x": 0.0, "y": 0.0, "z": 0.0, "w": 1.0 },
+            "Scale": { "x": 1.0, "y": 1.0, "z": 1.0 },
+            "BlockSize": { "x": 5.0, "y": 1.0, "z": 5.0 },
+            "Kinematic": true,
+        }],
+        "Enemies": [{
+            "ObjectIdentifier": "ultrakill.filth",
+            "Position": { "x": 0.0, "y": 0.0, "z": 0.0 },
+            "Rotation": { "x": 0.0, "y": 0.0, "z": 0.0, "w": 1.0 },
+            "Scale": { "x": 1.0, "y": 1.0, "z": 1.0 },
+            "Radiance": { "Tier": 2 },
+        }],
+    });
+
+    let mut save: SandboxSave = serde_json::from_value(json.clone()).unwrap();
+    assert!(save.props.is_empty());
+    assert_eq!(save.enemies[0].get_enemy_type(), Some(EnemyType::Filth));
+
+    let mut written = serde_json::to_value(&save).unwrap();
+    written.as_object_mut().unwrap().remove("Props");
+    assert_eq!(written, json);
+
+    assert!(save.enemies[0].set_enemy_type(EnemyType::Stalker));
+    let written = serde_json::to_value(&save).unwrap();
+    assert_eq!(
+        written["Enemies"][0]["ObjectIdentifier"],
+        Value::from("ultrakill.stalker")
+    );
+    assert_eq!(written["Enemies"][0]["Radiance"], json!({ "Tier": 2 }));
+}
+
+#[test]
+fn rename_and_duplicate() {
+    let dir = temp_dir().join(format!("sandbox-tests-{}", id()));
+    create_dir_all(&dir).unwrap();
+    let path = dir.join("arena.pitr");
+    write(&path, "{}").unwrap();
+
+    let copy = duplicate_save(&path, "copy").unwrap();
+    assert_eq!(copy, dir.join("copy.pitr"));
+    assert!(path.exists());
+    assert!(matches!(
+        rename_save(&path, "copy"),
+        Err(SandboxError::AlreadyExists(_))
+    ));
+    for name in ["", "..", "a/b", "a\\b"] {
+        assert!(matches!(
+            rename_save(&path, name),
+            Err(SandboxError::InvalidName(_))
+        ));
+    }
+
+    let renamed = rename_save(&path, "renamed").unwrap();
+    assert!(renamed.exists() && !path.exists());
+
+    remove_dir_all(&dir).unwrap();
+}
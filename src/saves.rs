@@ -0,0 +1,488 @@
+use crate::{
+    class::{
+        classes::Classes,
+        storage::{DirectoryStorage, MemoryStorage, Storage},
+    },
+    enums::{Difficulty, LevelRank, Lockable},
+};
+use registry::{Data, Hive, Security};
+use std::{
+    collections::BTreeMap,
+    env::var_os,
+    fmt,
+    fs::{create_dir_all, read_dir, read_to_string, write},
+    io,
+    path::{Component, Path, PathBuf},
+};
+use strum::IntoEnumIterator;
+
+pub fn detect_saves_root() -> Option<PathBuf> {
+    let regkey = Hive::LocalMachine
+        .open(
+            r"SOFTWARE\WOW6432Node\Valve\Steam",
+            Security::Read,
+        )
+        .ok()?;
+
+    let data = regkey.value("InstallPath").ok()?;
+
+    match data {
+        Data::String(path_str) => {
+            let mut path = PathBuf::from(path_str.to_os_string());
+            path.push(r"steamapps\common\ULTRAKILL\Saves");
+
+            if path.try_exists().ok()? {
+                Some(path)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slot {
+    pub name: String,
+    pub path: PathBuf,
+    pub bookmarked: bool,
+}
+
+impl fmt::Display for Slot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.bookmarked {
+            true => write!(f, "{} (bookmark)", self.name),
+            false => write!(f, "{}", self.name),
+        }
+    }
+}
+
+fn get_slot_number(name: &str) -> Option<u32> {
+    name.strip_prefix("Slot")?.parse().ok()
+}
+
+pub fn discover_slots(saves_root: Option<&Path>, bookmarks: &[PathBuf]) -> Vec<Slot> {
+    let mut slots = vec![];
+
+    if let Some(entries) = saves_root.and_then(|saves_root| read_dir(saves_root).ok()) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if name.starts_with("Slot") && entry.path().is_dir() {
+                slots.push(Slot {
+                    name,
+                    path: entry.path(),
+                    bookmarked: false,
+                });
+            }
+        }
+    }
+
+    slots.sort_by_key(|slot| {
+        let number = get_slot_number(&slot.name);
+        (number.is_none(), number, slot.name.clone())
+    });
+
+    for path in bookmarks {
+        if !slots.iter().any(|slot| slot.path == *path) {
+            slots.push(Slot {
+                name: get_slot_name(path),
+                path: path.clone(),
+                bookmarked: true,
+            });
+        }
+    }
+
+    slots
+}
+
+pub fn detect_slots() -> Vec<Slot> {
+    discover_slots(detect_saves_root().as_deref(), &load_bookmarks())
+}
+
+/// Finds a slot by name, by number (`3` for `Slot3`) or by path. Numbered
+/// slots that don't exist yet resolve to a new directory in `saves_root`.
+pub fn resolve_slot(saves_root: Option<&Path>, slots: &[Slot], slot: &str) -> Option<PathBuf> {
+    let numbered = format!("Slot{}", slot);
+
+    if let Some(slot) = slots.iter().find(|existing| {
+        existing.name.eq_ignore_ascii_case(slot) || existing.name.eq_ignore_ascii_case(&numbered)
+    }) {
+        return Some(slot.path.clone());
+    }
+
+    if let (Some(saves_root), Ok(_)) = (saves_root, slot.parse::<u32>()) {
+        return Some(saves_root.join(numbered));
+    }
+
+    let path = Path::new(slot);
+    match path.is_dir() {
+        true => Some(path.to_path_buf()),
+        false => None,
+    }
+}
+
+/// Path of one of the editor's own data files, such as bookmarks or presets.
+pub fn get_config_path(name: &str) -> Option<PathBuf> {
+    let config = match var_os("APPDATA") {
+        Some(app_data) => PathBuf::from(app_data),
+        None => PathBuf::from(var_os("HOME")?).join(".config"),
+    };
+
+    Some(config.join("ultrakill-save-editor").join(name))
+}
+
+fn get_bookmarks_path() -> Option<PathBuf> {
+    get_config_path("bookmarks.txt")
+}
+
+pub fn load_bookmarks() -> Vec<PathBuf> {
+    get_bookmarks_path()
+        .and_then(|path| read_to_string(path).ok())
+        .map(|bookmarks| {
+            bookmarks
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(PathBuf::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn save_bookmarks(bookmarks: &[PathBuf]) -> io::Result<()> {
+    let path = get_bookmarks_path().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "no configuration directory",
+        )
+    })?;
+
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+
+    write(
+        path,
+        bookmarks
+            .iter()
+            .map(|bookmark| bookmark.to_string_lossy().to_string() + "\n")
+            .collect::<String>(),
+    )
+}
+
+/// Bookmarks are compared by their canonical path, so the same directory
+/// reached through different paths is only bookmarked once.
+fn canonicalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+pub fn add_bookmark(path: &Path) -> io::Result<()> {
+    let path = canonicalize(path);
+    let mut bookmarks = load_bookmarks();
+
+    if !bookmarks
+        .iter()
+        .any(|bookmark| canonicalize(bookmark) == path)
+    {
+        bookmarks.push(path);
+    }
+
+    save_bookmarks(&bookmarks)
+}
+
+pub fn remove_bookmark(path: &Path) -> io::Result<()> {
+    let path = canonicalize(path);
+    let mut bookmarks = load_bookmarks();
+    bookmarks.retain(|bookmark| canonicalize(bookmark) != path);
+
+    save_bookmarks(&bookmarks)
+}
+
+/// Whether a name typed in by the user can be used as a file name, without
+/// reaching outside the directory it is saved in.
+pub fn is_valid_file_name(name: &str) -> bool {
+    !name.trim().is_empty()
+        && !name.contains(['/', '\\'])
+        && matches!(
+            Path::new(name).components().collect::<Vec<_>>()[..],
+            [Component::Normal(_)]
+        )
+}
+
+pub fn get_slot_name(save_path: &Path) -> String {
+    save_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| save_path.to_string_lossy().to_string())
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SlotSummary {
+    pub files: usize,
+    pub levels_completed: usize,
+    pub levels_completed_by_difficulty: BTreeMap<Difficulty, usize>,
+    pub p_ranks: usize,
+    pub secrets_found: usize,
+    pub primes_beaten: usize,
+    pub weapons_unlocked: usize,
+    pub money: String,
+    pub best_wave: Option<f32>,
+}
+
+impl SlotSummary {
+    pub fn load<S: Storage>(storage: &S) -> io::Result<Self> {
+        let files = storage.save_files()?.len();
+
+        if files == 0 {
+            return Ok(Self::default());
+        }
+
+        Ok(match Classes::load(storage) {
+            Some(classes) => Self::from_classes(files, &classes),
+            None => Self {
+                files,
+                ..Default::default()
+            },
+        })
+    }
+
+    /// Summarizes classes that haven't been written to a slot, counting the
+    /// files they would be saved as.
+    pub fn from_unsaved(classes: &Classes) -> io::Result<Self> {
+        let mut storage = MemoryStorage::new();
+        classes.save(&mut storage)?;
+
+        Self::load(&storage)
+    }
+
+    pub fn from_classes(files: usize, classes: &Classes) -> Self {
+        let levels = classes.levels.values().filter(|level| level.file_exists);
+        let general = &classes.general;
+
+        Self {
+            files,
+            levels_completed: levels
+                .clone()
+                .filter(|level| level.ranks.iter().any(|rank| *rank != LevelRank::None))
+                .count(),
+            levels_completed_by_difficulty: Difficulty::iter()
+                .map(|difficulty| {
+                    (
+                        difficulty,
+                        levels
+                            .clone()
+                            .filter(|level| {
+                                level
+                                    .ranks
+                                    .get(difficulty as usize)
+                                    .is_some_and(|rank| *rank != LevelRank::None)
+                            })
+                            .count(),
+                    )
+                })
+                .collect(),
+            p_ranks: levels
+                .clone()
+                .flat_map(|level| level.ranks.iter())
+                .filter(|rank| **rank == LevelRank::P)
+                .count(),
+            secrets_found: levels
+                .flat_map(|level| level.secrets_found.iter())
+                .filter(|found| **found)
+                .count(),
+            primes_beaten: (0..3)
+                .filter(|i| {
+                    classes.difficulty.values().any(|difficulty| {
+                        difficulty.file_exists
+                            && difficulty.prime_levels.get(*i) == Some(&Lockable::Completed)
+                    })
+                })
+                .count(),
+            weapons_unlocked: match general.file_exists {
+                true => general
+                    .unlocked_weapons
+                    .iter()
+                    .filter(|(variant, unlocked)| !variant.is_unused() && **unlocked)
+                    .count(),
+                false => 0,
+            },
+            money: match general.file_exists {
+                true => general.money.clone(),
+                false => String::new(),
+            },
+            best_wave: match classes.cybergrind.file_exists {
+                true => classes
+                    .cybergrind
+                    .waves
+                    .iter()
+                    .filter_map(|wave| wave.parse::<f32>().ok())
+                    .reduce(f32::max),
+                false => None,
+            },
+        }
+    }
+}
+
+impl fmt::Display for SlotSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.files == 0 {
+            return write!(f, "empty");
+        }
+
+        write!(
+            f,
+            "{} files, {} levels completed, {} P-ranks, {} secrets found",
+            self.files, self.levels_completed, self.p_ranks, self.secrets_found
+        )?;
+
+        if !self.money.is_empty() {
+            write!(f, ", {}P", self.money)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn get_slot_summaries(slots: &[Slot]) -> io::Result<Vec<(Slot, SlotSummary)>> {
+    let mut summaries = vec![];
+
+    for slot in slots {
+        summaries.push((
+            slot.clone(),
+            SlotSummary::load(&DirectoryStorage::new(&slot.path))?,
+        ));
+    }
+
+    Ok(summaries)
+}
+
+pub fn clear_slot<S: Storage>(storage: &mut S) -> io::Result<()> {
+    for name in storage.save_files()? {
+        storage.remove(&name)?;
+    }
+
+    Ok(())
+}
+
+fn overwrite_slot<T: Storage>(source: &MemoryStorage, target: &mut T) -> io::Result<()> {
+    clear_slot(target)?;
+
+    for name in source.save_files()? {
+        if let Some(data) = source.read(&name)? {
+            target.write(&name, &data)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Puts a slot's files back after a failed write, keeping the error that
+/// caused it.
+fn restore_slot<T: Storage>(backup: &MemoryStorage, target: &mut T, error: io::Error) -> io::Error {
+    match overwrite_slot(backup, target) {
+        Ok(()) => error,
+        Err(restore_error) => io::Error::new(
+            error.kind(),
+            format!(
+                "{}, and restoring the slot failed: {}",
+                error, restore_error
+            ),
+        ),
+    }
+}
+
+/// Replaces every save file in `target` with those in `source`. If a write
+/// fails, `target` is put back the way it was.
+pub fn copy_slot<S: Storage, T: Storage>(source: &S, target: &mut T) -> io::Result<()> {
+    let source = MemoryStorage::copy_from(source)?;
+    let backup = MemoryStorage::copy_from(target)?;
+
+    overwrite_slot(&source, target).map_err(|error| restore_slot(&backup, target, error))
+}
+
+pub fn swap_slots<S: Storage, T: Storage>(first: &mut S, second: &mut T) -> io::Result<()> {
+    let first_copy = MemoryStorage::copy_from(first)?;
+
+    copy_slot(second, first)?;
+    copy_slot(&first_copy, second).map_err(|error| restore_slot(&first_copy, first, error))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlotOperation {
+    Copy { source: PathBuf, target: PathBuf },
+    Swap(PathBuf, PathBuf),
+    Clear(PathBuf),
+}
+
+impl SlotOperation {
+    pub fn get_slots(&self) -> Vec<&Path> {
+        match self {
+            Self::Copy { source, target } => vec![source, target],
+            Self::Swap(first, second) => vec![first, second],
+            Self::Clear(path) => vec![path],
+        }
+    }
+
+    pub fn get_summaries(&self) -> io::Result<Vec<(String, SlotSummary)>> {
+        let mut summaries = vec![];
+
+        for path in self.get_slots() {
+            summaries.push((
+                get_slot_name(path),
+                SlotSummary::load(&DirectoryStorage::new(path))?,
+            ));
+        }
+
+        Ok(summaries)
+    }
+
+    pub fn apply(&self) -> io::Result<()> {
+        let slots = self.get_slots();
+
+        if slots.len() == 2 && slots[0] == slots[1] {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "source and target are the same slot",
+            ));
+        }
+
+        match self {
+            Self::Copy { source, target } => {
+                create_dir_all(target)?;
+                copy_slot(
+                    &DirectoryStorage::new(source),
+                    &mut DirectoryStorage::new(target),
+                )
+            }
+            Self::Swap(first, second) => {
+                create_dir_all(first)?;
+                create_dir_all(second)?;
+                swap_slots(
+                    &mut DirectoryStorage::new(first),
+                    &mut DirectoryStorage::new(second),
+                )
+            }
+            Self::Clear(path) => clear_slot(&mut DirectoryStorage::new(path)),
+        }
+    }
+}
+
+impl fmt::Display for SlotOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Copy { source, target } => write!(
+                f,
+                "Copy {} to {}",
+                get_slot_name(source),
+                get_slot_name(target)
+            ),
+            Self::Swap(first, second) => write!(
+                f,
+                "Swap {} and {}",
+                get_slot_name(first),
+                get_slot_name(second)
+            ),
+            Self::Clear(path) => write!(f, "Clear {}", get_slot_name(path)),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{
+    class::general::GeneralData,
+    enums::{UnlockableWeaponVariant, WeaponType},
+};
+use strum::IntoEnumIterator;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ShopError {
+    #[error("{0} isn't sold in the shop")]
+    NotForSale(UnlockableWeaponVariant),
+    #[error("{0} is already unlocked")]
+    AlreadyUnlocked(UnlockableWeaponVariant),
+    #[error("{0} isn't unlocked")]
+    NotUnlocked(UnlockableWeaponVariant),
+    #[error("{0} needs {1} first")]
+    MissingBase(UnlockableWeaponVariant, UnlockableWeaponVariant),
+    #[error("{0} costs {1}P but there is only {2}P")]
+    NotEnoughMoney(UnlockableWeaponVariant, u32, u32),
+    #[error("money is not a valid amount")]
+    InvalidMoney,
+}
+
+fn is_unlocked(general: &GeneralData, variant: &UnlockableWeaponVariant) -> bool {
+    general.unlocked_weapons.get(variant) == Some(&true)
+}
+
+fn get_weapon(variant: &UnlockableWeaponVariant) -> Option<WeaponType> {
+    WeaponType::iter().find(|weapon| weapon.get_unlockable_variants().contains(variant))
+}
+
+pub fn get_money(general: &GeneralData) -> Result<u32, ShopError> {
+    match general.money.is_empty() {
+        true => Ok(0),
+        false => general.money.parse().map_err(|_| ShopError::InvalidMoney),
+    }
+}
+
+fn set_money(general: &mut GeneralData, money: u32) -> Result<(), ShopError> {
+    if i32::try_from(money).is_err() {
+        return Err(ShopError::InvalidMoney);
+    }
+
+    general.money = money.to_string();
+    general.file_exists = true;
+    Ok(())
+}
+
+/// Total P spent on the variants that are unlocked.
+pub fn get_spent(general: &GeneralData) -> u32 {
+    UnlockableWeaponVariant::iter()
+        .filter(|variant| is_unlocked(general, variant))
+        .filter_map(|variant| variant.get_price())
+        .sum()
+}
+
+/// Variants in the shop that are still locked.
+pub fn get_buyable(general: &GeneralData) -> Vec<UnlockableWeaponVariant> {
+    UnlockableWeaponVariant::iter()
+        .filter(|variant| variant.get_price().is_some() && !is_unlocked(general, variant))
+        .collect()
+}
+
+/// Unlocks a variant, taking its price from the money.
+pub fn buy(general: &mut GeneralData, variant: &UnlockableWeaponVariant) -> Result<(), ShopError> {
+    let price = variant.get_price().ok_or(ShopError::NotForSale(*variant))?;
+
+    if is_unlocked(general, variant) {
+        return Err(ShopError::AlreadyUnlocked(*variant));
+    }
+
+    if let Some(base) = get_weapon(variant).and_then(|weapon| weapon.get_base_variant()) {
+        if !is_unlocked(general, &base) {
+            return Err(ShopError::MissingBase(*variant, base));
+        }
+    }
+
+    let money = get_money(general)?;
+    let left = money
+        .checked_sub(price)
+        .ok_or(ShopError::NotEnoughMoney(*variant, price, money))?;
+
+    set_money(general, left)?;
+    general.unlocked_weapons.insert(*variant, true);
+    Ok(())
+}
+
+/// Locks a variant, giving its price back.
+pub fn refund(
+    general: &mut GeneralData,
+    variant: &UnlockableWeaponVariant,
+) -> Result<(), ShopError> {
+    let price = variant.get_price().ok_or(ShopError::NotForSale(*variant))?;
+
+    if !is_unlocked(general, variant) {
+        return Err(ShopError::NotUnlocked(*variant));
+    }
+
+    let money = get_money(general)?
+        .checked_add(price)
+        .ok_or(ShopError::InvalidMoney)?;
+
+    set_money(general, money)?;
+    general.unlocked_weapons.insert(*variant, false);
+    Ok(())
+}
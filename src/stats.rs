@@ -0,0 +1,239 @@
+use crate::{
+    class::{classes::Classes, level::LevelData},
+    enums::{
+        Act, Difficulty, EnemyType, Layer, Level, LevelRank, Lockable, SecretLevel,
+        UnlockableWeaponVariant,
+    },
+};
+use std::fmt;
+use strum::IntoEnumIterator;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Progress {
+    pub done: usize,
+    pub total: usize,
+}
+
+impl Progress {
+    pub fn percent(&self) -> f32 {
+        match self.total {
+            0 => 100.0,
+            total => self.done as f32 / total as f32 * 100.0,
+        }
+    }
+
+    fn add(&mut self, done: bool) {
+        self.total += 1;
+        if done {
+            self.done += 1;
+        }
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{} ({:.1}%)", self.done, self.total, self.percent())
+    }
+}
+
+pub const COMPLETION_COLUMNS: [&str; 6] = [
+    "Cleared",
+    "S-ranks",
+    "P-ranks",
+    "Secrets",
+    "Challenges",
+    "No major assists",
+];
+
+/// Completion of a group of levels. Ranks and assists are taken from one
+/// difficulty, or from the best difficulty of each level when there is none.
+/// Secrets and challenges are shared by every difficulty.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Completion {
+    pub cleared: Progress,
+    pub s_ranks: Progress,
+    pub p_ranks: Progress,
+    pub secrets: Progress,
+    pub challenges: Progress,
+    pub no_major_assists: Progress,
+}
+
+impl Completion {
+    pub fn new(classes: &Classes, levels: &[Level], difficulty: Option<Difficulty>) -> Self {
+        let mut completion = Self::default();
+
+        for level in levels {
+            let data = classes.levels.get(level).filter(|data| data.file_exists);
+
+            let ranks: Vec<(LevelRank, bool)> = match data {
+                Some(data) => match difficulty {
+                    Some(difficulty) => vec![get_rank(data, difficulty as usize)],
+                    None => (0..data.ranks.len())
+                        .map(|i| get_rank(data, i))
+                        .collect(),
+                },
+                None => vec![],
+            };
+            let cleared = ranks.iter().any(|(rank, _)| *rank != LevelRank::None);
+
+            completion.cleared.add(cleared);
+            completion.s_ranks.add(
+                ranks
+                    .iter()
+                    .any(|(rank, _)| matches!(rank, LevelRank::S | LevelRank::P)),
+            );
+            completion
+                .p_ranks
+                .add(ranks.iter().any(|(rank, _)| *rank == LevelRank::P));
+            completion.no_major_assists.add(
+                ranks
+                    .iter()
+                    .any(|(rank, assisted)| *rank != LevelRank::None && !assisted),
+            );
+
+            if !level.is_prime() {
+                completion
+                    .challenges
+                    .add(data.is_some_and(|data| data.challenge));
+            }
+
+            completion.secrets.total += level.get_secret_count() as usize;
+            completion.secrets.done += data
+                .map(|data| data.secrets_found.iter().filter(|found| **found).count())
+                .unwrap_or_default();
+        }
+
+        completion
+    }
+
+    pub fn columns(&self) -> [Progress; 6] {
+        [
+            self.cleared,
+            self.s_ranks,
+            self.p_ranks,
+            self.secrets,
+            self.challenges,
+            self.no_major_assists,
+        ]
+    }
+}
+
+fn get_rank(data: &LevelData, index: usize) -> (LevelRank, bool) {
+    (
+        data.ranks.get(index).copied().unwrap_or_default(),
+        data.major_assists.get(index).copied().unwrap_or_default(),
+    )
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    pub overall: Completion,
+    pub by_difficulty: Vec<(Difficulty, Completion)>,
+    pub by_act: Vec<(Act, Completion)>,
+    pub by_layer: Vec<(Layer, Completion)>,
+    pub secret_missions: Progress,
+    pub primes: Progress,
+    pub bestiary: Progress,
+    pub weapon_variants: Progress,
+}
+
+impl Stats {
+    pub fn new(classes: &Classes) -> Self {
+        let levels: Vec<Level> = Level::iter().collect();
+        let general = classes.general.file_exists.then_some(&classes.general);
+
+        let mut secret_missions = Progress::default();
+        for secret_level in
+            SecretLevel::iter().filter(|level| !level.is_prime() && !level.is_placeholder())
+        {
+            secret_missions.add(general.is_some_and(|general| {
+                general.secret_missions.get(&secret_level) == Some(&Lockable::Completed)
+            }));
+        }
+
+        let mut primes = Progress::default();
+        for prime_index in levels.iter().filter_map(|level| level.get_prime_index()) {
+            primes.add(classes.difficulty.values().any(|difficulty| {
+                difficulty.file_exists
+                    && difficulty.prime_levels.get(prime_index as usize)
+                        == Some(&Lockable::Completed)
+            }));
+        }
+
+        let mut bestiary = Progress::default();
+        for enemy in EnemyType::iter() {
+            bestiary.add(general.is_some_and(|general| {
+                general
+                    .enemies_discovered
+                    .get(&enemy)
+                    .is_some_and(|lockable| *lockable != Lockable::Locked)
+            }));
+        }
+
+        let mut weapon_variants = Progress::default();
+        for variant in UnlockableWeaponVariant::iter().filter(|variant| !variant.is_unused()) {
+            weapon_variants.add(general.is_some_and(|general| {
+                general.unlocked_weapons.get(&variant) == Some(&true)
+            }));
+        }
+
+        Self {
+            overall: Completion::new(classes, &levels, None),
+            by_difficulty: Difficulty::iter()
+                .map(|difficulty| {
+                    (difficulty, Completion::new(classes, &levels, Some(difficulty)))
+                })
+                .collect(),
+            by_act: Act::iter()
+                .map(|act| {
+                    let levels: Vec<Level> = act
+                        .get_layers()
+                        .iter()
+                        .flat_map(|layer| layer.get_levels().iter().copied())
+                        .collect();
+                    (act, Completion::new(classes, &levels, None))
+                })
+                .collect(),
+            by_layer: Layer::iter()
+                .filter(|layer| !layer.get_levels().is_empty())
+                .map(|layer| (layer, Completion::new(classes, layer.get_levels(), None)))
+                .collect(),
+            secret_missions,
+            primes,
+            bestiary,
+            weapon_variants,
+        }
+    }
+
+    /// Every completion group with a label, in the order they are displayed.
+    pub fn groups(&self) -> Vec<(String, &Completion)> {
+        let mut groups = vec![("Overall".to_string(), &self.overall)];
+
+        groups.extend(
+            self.by_difficulty
+                .iter()
+                .map(|(difficulty, completion)| (difficulty.to_string(), completion)),
+        );
+        groups.extend(
+            self.by_act
+                .iter()
+                .map(|(act, completion)| (act.to_string(), completion)),
+        );
+        groups.extend(
+            self.by_layer
+                .iter()
+                .map(|(layer, completion)| (layer.to_string(), completion)),
+        );
+
+        groups
+    }
+
+    pub fn totals(&self) -> [(&'static str, Progress); 4] {
+        [
+            ("Secret missions", self.secret_missions),
+            ("Primes", self.primes),
+            ("Bestiary", self.bestiary),
+            ("Weapon variants", self.weapon_variants),
+        ]
+    }
+}
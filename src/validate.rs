@@ -0,0 +1,300 @@
+use crate::{
+    class::classes::Classes,
+    enums::{CampaignPosition, Level, LevelRank, Lockable, SecretLevel, WeaponType},
+    loadout::get_conflicts,
+};
+use std::fmt;
+use strum::IntoEnumIterator;
+
+/// A contradiction in the save that the game may not handle, with the field
+/// it was found in and what to change to fix it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub path: String,
+    pub message: String,
+    pub fix: String,
+}
+
+impl Warning {
+    fn new(path: String, message: String, fix: String) -> Self {
+        Self { path, message, fix }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}. {}", self.path, self.message, self.fix)
+    }
+}
+
+/// Ranks and major assists have a slot for every difficulty the game may
+/// add, not just the ones it has now.
+const DIFFICULTY_SLOTS: usize = 6;
+
+type Rule = fn(&Classes, &mut Vec<Warning>);
+
+const RULES: &[Rule] = &[
+    check_lengths,
+    check_current_level,
+    check_challenges,
+    check_primes,
+    check_clash_mode,
+    check_limbo_switches,
+    check_weapons,
+];
+
+/// Runs every rule over the parts of the save that will be written.
+pub fn validate(classes: &Classes) -> Vec<Warning> {
+    let mut warnings = vec![];
+    for rule in RULES {
+        rule(classes, &mut warnings);
+    }
+
+    warnings
+}
+
+/// The part of a name before the `:`, such as `4-2` or `P-1`.
+fn get_code(value: &impl fmt::Display) -> String {
+    value
+        .to_string()
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn check_length<T>(warnings: &mut Vec<Warning>, path: String, values: &[T], expected: usize) {
+    if values.len() != expected {
+        warnings.push(Warning::new(
+            path,
+            format!("has {} entries instead of {}", values.len(), expected),
+            format!("Resize it to {} entries", expected),
+        ));
+    }
+}
+
+fn check_lengths(classes: &Classes, warnings: &mut Vec<Warning>) {
+    for (level, data) in classes.levels.iter().filter(|(_, data)| data.file_exists) {
+        let code = get_code(level);
+        check_length(
+            warnings,
+            format!("levels[{}].ranks", code),
+            &data.ranks,
+            DIFFICULTY_SLOTS,
+        );
+        check_length(
+            warnings,
+            format!("levels[{}].major_assists", code),
+            &data.major_assists,
+            DIFFICULTY_SLOTS,
+        );
+        check_length(
+            warnings,
+            format!("levels[{}].secrets_found", code),
+            &data.secrets_found,
+            level.get_secret_count() as usize,
+        );
+    }
+
+    for (difficulty, data) in classes
+        .difficulty
+        .iter()
+        .filter(|(_, data)| data.file_exists)
+    {
+        check_length(
+            warnings,
+            format!("difficulty[{}].prime_levels", difficulty),
+            &data.prime_levels,
+            3,
+        );
+    }
+
+    if classes.general.file_exists {
+        check_length(
+            warnings,
+            "general.limbo_switches".to_string(),
+            &classes.general.limbo_switches,
+            4,
+        );
+    }
+}
+
+/// The current level is the next one to unlock, so a rank on it or on any
+/// story level after it could not have been earned.
+fn check_current_level(classes: &Classes, warnings: &mut Vec<Warning>) {
+    for (difficulty, data) in classes
+        .difficulty
+        .iter()
+        .filter(|(_, data)| data.file_exists)
+    {
+        let ranked: Vec<Level> = classes
+            .levels
+            .iter()
+            .filter(|(level, level_data)| {
+                !level.is_prime()
+                    && !data.current_level.is_past(level)
+                    && level_data.file_exists
+                    && level_data
+                        .ranks
+                        .get(*difficulty as usize)
+                        .is_some_and(|rank| *rank != LevelRank::None)
+            })
+            .map(|(level, _)| *level)
+            .collect();
+
+        let Some(last) = ranked.last() else {
+            continue;
+        };
+
+        warnings.push(Warning::new(
+            format!("difficulty[{}].current_level", difficulty),
+            format!(
+                "{} {} ranked but the current level is {}",
+                ranked
+                    .iter()
+                    .map(get_code)
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                match ranked.len() {
+                    1 => "is",
+                    _ => "are",
+                },
+                get_code(&data.current_level)
+            ),
+            format!(
+                "Set the current level to {} or clear those ranks",
+                get_code(&CampaignPosition::after(last))
+            ),
+        ));
+    }
+}
+
+fn check_challenges(classes: &Classes, warnings: &mut Vec<Warning>) {
+    for (level, data) in classes.levels.iter() {
+        if data.file_exists
+            && data.challenge
+            && data.ranks.iter().all(|rank| *rank == LevelRank::None)
+        {
+            warnings.push(Warning::new(
+                format!("levels[{}].challenge", get_code(level)),
+                "the challenge is done but the level has no rank on any difficulty".to_string(),
+                "Give the level a rank or reset the challenge".to_string(),
+            ));
+        }
+    }
+}
+
+/// Completing a prime sanctum also completes its secret mission, which is
+/// what the game uses to show it in the level select.
+fn check_primes(classes: &Classes, warnings: &mut Vec<Warning>) {
+    for level in Level::iter() {
+        let (Some(prime_index), Some(layer)) = (level.get_prime_index(), level.get_layer()) else {
+            continue;
+        };
+
+        let completed: Vec<String> = classes
+            .difficulty
+            .iter()
+            .filter(|(_, data)| {
+                data.file_exists
+                    && data.prime_levels.get(prime_index as usize) == Some(&Lockable::Completed)
+            })
+            .map(|(difficulty, _)| difficulty.to_string())
+            .collect();
+
+        let secret_level = layer.get_secret_level();
+        let state = classes
+            .general
+            .secret_missions
+            .get(&secret_level)
+            .copied()
+            .unwrap_or_default();
+
+        if !completed.is_empty() && state == Lockable::Locked {
+            warnings.push(Warning::new(
+                format!("general.secret_missions[{}]", get_code(&secret_level)),
+                format!(
+                    "{} is completed on {} but its secret mission is locked",
+                    get_code(&level),
+                    completed.join(", ")
+                ),
+                "Set the secret mission to completed".to_string(),
+            ));
+        }
+    }
+}
+
+fn check_clash_mode(classes: &Classes, warnings: &mut Vec<Warning>) {
+    let general = &classes.general;
+    let secret_level = SecretLevel::ClashOfTheBrandicoot;
+
+    if general.file_exists
+        && general.clash_mode_unlocked
+        && general.secret_missions.get(&secret_level) != Some(&Lockable::Completed)
+    {
+        warnings.push(Warning::new(
+            "general.clash_mode_unlocked".to_string(),
+            format!(
+                "clash mode is unlocked but {} is not completed",
+                get_code(&secret_level)
+            ),
+            format!("Complete {} or lock clash mode", get_code(&secret_level)),
+        ));
+    }
+}
+
+/// 1-S only opens once all four switches in Limbo have been pressed.
+fn check_limbo_switches(classes: &Classes, warnings: &mut Vec<Warning>) {
+    let general = &classes.general;
+    let secret_level = SecretLevel::TheWitless;
+    let set = general.limbo_switches.iter().filter(|set| **set).count();
+    let all_set = set == general.limbo_switches.len();
+    let state = general
+        .secret_missions
+        .get(&secret_level)
+        .copied()
+        .unwrap_or_default();
+
+    if !general.file_exists {
+        return;
+    }
+
+    if all_set && state == Lockable::Locked {
+        warnings.push(Warning::new(
+            format!("general.secret_missions[{}]", get_code(&secret_level)),
+            format!(
+                "every limbo switch is set but {} is locked",
+                get_code(&secret_level)
+            ),
+            format!("Unlock {}", get_code(&secret_level)),
+        ));
+    } else if !all_set && state != Lockable::Locked {
+        warnings.push(Warning::new(
+            "general.limbo_switches".to_string(),
+            format!(
+                "{} is {} but only {} of {} limbo switches are set",
+                get_code(&secret_level),
+                state,
+                set,
+                general.limbo_switches.len()
+            ),
+            format!("Set every switch or lock {}", get_code(&secret_level)),
+        ));
+    }
+}
+
+fn check_weapons(classes: &Classes, warnings: &mut Vec<Warning>) {
+    if !classes.general.file_exists {
+        return;
+    }
+
+    for weapon in WeaponType::iter() {
+        for conflict in get_conflicts(&classes.general, &weapon) {
+            warnings.push(Warning::new(
+                conflict.path.clone(),
+                conflict.to_string(),
+                format!("Unlock {} or lock {}", conflict.base, conflict.unlocked),
+            ));
+        }
+    }
+}